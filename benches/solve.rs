@@ -0,0 +1,92 @@
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use kctf_pow::{ChallengeParams, Integer, KctfPow, Verifier};
+
+const DIFFICULTIES: [u32; 2] = [1, 10];
+
+/// Flips the lowest bit of a value.
+fn flip_low_bit(val: Integer) -> Integer {
+    if val.is_odd() {
+        val - 1u32
+    } else {
+        val + 1u32
+    }
+}
+
+/// Solves a challenge the way the solver did before it had a dedicated squaring kernel.
+fn generic_solve(mut val: Integer, difficulty: u32, pow: &KctfPow) -> Integer {
+    for _ in 0..difficulty {
+        val = flip_low_bit(val.pow_mod(&pow.exponent, &pow.modulus));
+    }
+    val
+}
+
+/// Verifies a solution the way the checker did before it had a dedicated squaring kernel.
+fn generic_check(mut val: Integer, difficulty: u32, pow: &KctfPow) -> Integer {
+    for _ in 0..difficulty {
+        val = flip_low_bit(val);
+        val = val.clone() * &val % &pow.modulus;
+    }
+    val
+}
+
+fn bench_solve(c: &mut Criterion) {
+    let pow = KctfPow::new();
+    let mut group = c.benchmark_group("solve");
+    for difficulty in DIFFICULTIES {
+        let chall = ChallengeParams::generate_challenge(difficulty);
+        group.bench_with_input(
+            BenchmarkId::new("mersenne", difficulty),
+            &chall,
+            |b, chall| b.iter(|| chall.clone().solve(&pow)),
+        );
+        group.bench_with_input(
+            BenchmarkId::new("generic", difficulty),
+            &chall,
+            |b, chall| b.iter(|| generic_solve(chall.val.clone(), chall.difficulty, &pow)),
+        );
+    }
+    group.finish();
+}
+
+fn bench_check(c: &mut Criterion) {
+    let pow = KctfPow::new();
+    let mut group = c.benchmark_group("check");
+    for difficulty in DIFFICULTIES {
+        let chall = ChallengeParams::generate_challenge(difficulty);
+        let sol = chall.clone().solve(&pow).to_string();
+        let sol_val = generic_solve(chall.val.clone(), chall.difficulty, &pow);
+        group.bench_with_input(
+            BenchmarkId::new("mersenne", difficulty),
+            &chall,
+            |b, chall| b.iter(|| chall.check(&pow, &sol)),
+        );
+        group.bench_with_input(
+            BenchmarkId::new("generic", difficulty),
+            &chall,
+            |b, chall| b.iter(|| generic_check(sol_val.clone(), chall.difficulty, &pow)),
+        );
+    }
+    group.finish();
+}
+
+fn bench_verifier(c: &mut Criterion) {
+    let pow = KctfPow::new();
+    let mut verifier = Verifier::new(&pow);
+    let mut group = c.benchmark_group("verifier");
+    for difficulty in DIFFICULTIES {
+        let chall = ChallengeParams::generate_challenge(difficulty);
+        let sol = chall.clone().solve(&pow).to_string();
+        group.bench_with_input(
+            BenchmarkId::new("verifier", difficulty),
+            &chall,
+            |b, chall| b.iter(|| verifier.check(chall, &sol)),
+        );
+        group.bench_with_input(BenchmarkId::new("check", difficulty), &chall, |b, chall| {
+            b.iter(|| chall.check(&pow, &sol))
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_solve, bench_check, bench_verifier);
+criterion_main!(benches);
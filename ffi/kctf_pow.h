@@ -0,0 +1,132 @@
+#ifndef KCTF_POW_H
+#define KCTF_POW_H
+
+/* This file is generated by cbindgen from src/ffi.rs. Do not edit it by hand. */
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+// The result of a call that can fail.
+//
+// Decoding and checking errors have the same values as the exit codes of the CLI.
+typedef enum KctfPowStatus {
+  // The call succeeded.
+  KCTF_POW_STATUS_OK = 0,
+  // A pointer argument was null, or a string argument wasn't valid UTF-8.
+  KCTF_POW_STATUS_INVALID_ARGUMENT = 1,
+  // The version prefix isn't the one this library supports.
+  KCTF_POW_STATUS_WRONG_VERSION = 2,
+  // The input doesn't have the right number of `.`-separated parts.
+  KCTF_POW_STATUS_WRONG_PART_COUNT = 3,
+  // A part isn't valid base64.
+  KCTF_POW_STATUS_INVALID_BASE64 = 4,
+  // The difficulty doesn't fit in 32 bits.
+  KCTF_POW_STATUS_DIFFICULTY_TOO_LARGE = 5,
+  // A value is outside of the range it's allowed to be in.
+  KCTF_POW_STATUS_VALUE_OUT_OF_RANGE = 6,
+  // The input ended before all of its data was read.
+  KCTF_POW_STATUS_TRUNCATED = 7,
+  // The input isn't in a form that this library or kCTF encodes it in.
+  KCTF_POW_STATUS_NON_CANONICAL = 8,
+  // The input is longer than any valid input could be.
+  KCTF_POW_STATUS_TOO_LONG = 9,
+  // An authentication tag doesn't match.
+  KCTF_POW_STATUS_AUTHENTICATION_FAILED = 10,
+  // The modulus isn't valid.
+  KCTF_POW_STATUS_INVALID_MODULUS = 11,
+} KctfPowStatus;
+
+// A proof-of-work system for kCTF.
+//
+// All proof-of-work related methods are on instances of [`KctfPow`] in order to initialize and reuse related constants.
+typedef struct KctfPow KctfPow;
+
+// The parameters of a challenge, which don't depend on the proof-of-work system that solves or checks them.
+typedef struct KctfPowChallenge KctfPowChallenge;
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+// Creates a proof-of-work system using kCTF's modulus. It must be freed with `kctf_pow_free`.
+struct KctfPow *kctf_pow_new(void);
+
+// Frees a proof-of-work system. Passing null does nothing.
+//
+// # Safety
+//
+// `pow` must be null or have been returned by `kctf_pow_new`, and must not be used afterwards.
+void kctf_pow_free(struct KctfPow *pow);
+
+// Decodes a challenge from a string.
+//
+// On success, `*out` is set to the challenge, which must be freed with `kctf_pow_challenge_free`.
+// On failure, `*out` is left unchanged.
+//
+// # Safety
+//
+// `chall` must be null or a null-terminated string, and `out` must be null or valid for writes.
+enum KctfPowStatus kctf_pow_decode_challenge(const char *chall, struct KctfPowChallenge **out);
+
+// Generates a random challenge given a difficulty. It must be freed with `kctf_pow_challenge_free`.
+struct KctfPowChallenge *kctf_pow_generate_challenge(uint32_t difficulty);
+
+// Returns the difficulty of a challenge, or 0 if `chall` is null.
+//
+// # Safety
+//
+// `chall` must be null or a live challenge.
+uint32_t kctf_pow_challenge_difficulty(const struct KctfPowChallenge *chall);
+
+// Encodes a challenge into a string, which must be freed with `kctf_pow_string_free`. Returns null if `chall` is null.
+//
+// # Safety
+//
+// `chall` must be null or a live challenge.
+char *kctf_pow_challenge_to_string(const struct KctfPowChallenge *chall);
+
+// Frees a challenge. Passing null does nothing.
+//
+// # Safety
+//
+// `chall` must be null or have been returned by this library, and must not be used afterwards.
+void kctf_pow_challenge_free(struct KctfPowChallenge *chall);
+
+// Solves a challenge and returns the solution as a string, which must be freed with `kctf_pow_string_free`.
+// Returns null if either argument is null.
+//
+// # Safety
+//
+// `pow` and `chall` must each be null or live.
+char *kctf_pow_solve(const struct KctfPow *pow,
+                     const struct KctfPowChallenge *chall);
+
+// Checks a solution string to see if it satisfies a challenge.
+//
+// On success, `*valid` is set to whether the solution is correct. A solution that can't be decoded is an error
+// rather than an incorrect solution, in which case `*valid` is left unchanged.
+//
+// # Safety
+//
+// `pow` and `chall` must each be null or live, `sol` must be null or a null-terminated string,
+// and `valid` must be null or valid for writes.
+enum KctfPowStatus kctf_pow_check(const struct KctfPow *pow,
+                                  const struct KctfPowChallenge *chall,
+                                  const char *sol,
+                                  bool *valid);
+
+// Frees a string returned by this library. Passing null does nothing.
+//
+// # Safety
+//
+// `string` must be null or have been returned by this library, and must not be used afterwards.
+void kctf_pow_string_free(char *string);
+
+#ifdef __cplusplus
+}  // extern "C"
+#endif  // __cplusplus
+
+#endif  /* KCTF_POW_H */
@@ -0,0 +1,89 @@
+// Exercises the C ABI. Build and run it with `make -C ffi test`.
+
+#include <assert.h>
+#include <stdio.h>
+#include <string.h>
+
+#include "kctf_pow.h"
+
+static const char *CHALLENGE = "s.AAAAMg==.NDtqORW1uZlIgzszbdMGZA==";
+static const char *SOLUTION =
+    "s.NUH3arymnKB+ysUGdv+67ypDamn4wOKCPORB2ivWE1Yhinam2v4S6q4nAoC5LP97LScdVoq+NuFVF++Win5mNRYZS6bJAs8fk0h8XgvfcC/"
+    "7JfmFISqeCIo/CIUgIucVAM+eGDjqitRULGXqIOyviJoJjW8DMouMRuJM/3eg/z18kutQHkX0N3sqPeF7Nzkk8S3Bs6aiHUORM30syUKYug==";
+
+static void test_decode_solve_check(const KctfPow *pow) {
+    KctfPowChallenge *chall = NULL;
+    assert(kctf_pow_decode_challenge(CHALLENGE, &chall) == KCTF_POW_STATUS_OK);
+    assert(kctf_pow_challenge_difficulty(chall) == 50);
+
+    char *encoded = kctf_pow_challenge_to_string(chall);
+    assert(strcmp(encoded, CHALLENGE) == 0);
+    kctf_pow_string_free(encoded);
+
+    char *sol = kctf_pow_solve(pow, chall);
+    assert(strcmp(sol, SOLUTION) == 0);
+    kctf_pow_string_free(sol);
+
+    bool valid = false;
+    assert(kctf_pow_check(pow, chall, SOLUTION, &valid) == KCTF_POW_STATUS_OK);
+    assert(valid);
+    assert(kctf_pow_check(pow, chall, "s.asdf", &valid) == KCTF_POW_STATUS_OK);
+    assert(!valid);
+    assert(kctf_pow_check(pow, chall, "t.asdf", &valid) == KCTF_POW_STATUS_WRONG_VERSION);
+    assert(kctf_pow_check(pow, chall, "s.as.df", &valid) == KCTF_POW_STATUS_WRONG_PART_COUNT);
+
+    kctf_pow_challenge_free(chall);
+}
+
+static void test_generate(const KctfPow *pow) {
+    KctfPowChallenge *chall = kctf_pow_generate_challenge(10);
+    assert(kctf_pow_challenge_difficulty(chall) == 10);
+
+    // round trip through the string form, as a launcher handing out challenges would
+    char *encoded = kctf_pow_challenge_to_string(chall);
+    KctfPowChallenge *decoded = NULL;
+    assert(kctf_pow_decode_challenge(encoded, &decoded) == KCTF_POW_STATUS_OK);
+    kctf_pow_string_free(encoded);
+
+    char *sol = kctf_pow_solve(pow, decoded);
+    bool valid = false;
+    assert(kctf_pow_check(pow, chall, sol, &valid) == KCTF_POW_STATUS_OK);
+    assert(valid);
+    kctf_pow_string_free(sol);
+
+    kctf_pow_challenge_free(decoded);
+    kctf_pow_challenge_free(chall);
+}
+
+static void test_errors(const KctfPow *pow) {
+    KctfPowChallenge *chall = NULL;
+    assert(kctf_pow_decode_challenge("x.AAAAMg==.AAAA", &chall) == KCTF_POW_STATUS_WRONG_VERSION);
+    assert(kctf_pow_decode_challenge("s.AAAAMg==", &chall) == KCTF_POW_STATUS_WRONG_PART_COUNT);
+    assert(kctf_pow_decode_challenge("s.AAAAMg==.!!!!", &chall) == KCTF_POW_STATUS_INVALID_BASE64);
+    assert(kctf_pow_decode_challenge("s.AQAAAAAA.AAAA", &chall) == KCTF_POW_STATUS_DIFFICULTY_TOO_LARGE);
+    assert(kctf_pow_decode_challenge("s.AAAAMg==.\xff", &chall) == KCTF_POW_STATUS_INVALID_ARGUMENT);
+    assert(kctf_pow_decode_challenge(NULL, &chall) == KCTF_POW_STATUS_INVALID_ARGUMENT);
+    assert(kctf_pow_decode_challenge(CHALLENGE, NULL) == KCTF_POW_STATUS_INVALID_ARGUMENT);
+    // failed decodes leave the output alone
+    assert(chall == NULL);
+
+    bool valid = false;
+    assert(kctf_pow_check(pow, NULL, SOLUTION, &valid) == KCTF_POW_STATUS_INVALID_ARGUMENT);
+    assert(kctf_pow_solve(pow, NULL) == NULL);
+    assert(kctf_pow_challenge_to_string(NULL) == NULL);
+
+    // freeing null does nothing
+    kctf_pow_free(NULL);
+    kctf_pow_challenge_free(NULL);
+    kctf_pow_string_free(NULL);
+}
+
+int main(void) {
+    KctfPow *pow = kctf_pow_new();
+    test_decode_solve_check(pow);
+    test_generate(pow);
+    test_errors(pow);
+    kctf_pow_free(pow);
+    puts("All tests passed");
+    return 0;
+}
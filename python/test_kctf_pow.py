@@ -0,0 +1,101 @@
+"""Tests that the bindings are interchangeable with kCTF's pow.py.
+
+Build the module into the current environment with `maturin develop`, then run `python -m unittest discover python`.
+"""
+
+import base64
+import unittest
+
+import kctf_pow
+
+MODULUS = 2**1279 - 1
+
+# challenges and solutions generated and solved by the reference algorithm below
+VECTORS = [
+    (
+        "s.AAAB.AABfSF1Yn8Q0sHz4s6q7r/ui",
+        "s.AAA8de5IS5SVj6Oc9VTgDAjaqjJrhY1lSsh1j2eHJ0yzX/fDW4V1gdW2KN/h01nwKnR79xqVaVO9ySpf7xObxEGu2uQ5swYMKkn0l/O71L84rooXROQk1UEDavEu8EE3vuFogWYpltF6BTRVy5ZyUrGd+PN2yR5xK3touJCctj0uM02FsK8nSxVEjYNl9A+gK9BrL4303rsFq1q3tGWXZKcb",
+    ),
+    (
+        "s.AAAC.AACrNon/HLkAT1BtKdFZIf7/",
+        "s.AABwvVoC5TuIKISpX4m7XmF5LADvx3HfJkWmZDTknl6xfwheV8U6MECCgKPAgRU6ksV/7ti23qBD6pbj7i0YvGirYcyMceBiXqhytLPMojKx6ZFOTg2kF3BUVmPbyJdggLZvF5t7kqZXmoR/JxOplwd4iEKQjMDSCXGV8Z8pdihMfmlrB/lpWFGhaHEQQkjiyRVWL3mRy/o8dQq5B/xuqyTr",
+    ),
+    (
+        "s.AAAK.AABlNHXm+t9maf9mXjYJ4T+8",
+        "s.AABnHEjFhwMtb3EN19v2CFnfVdyZNIyCCNpJU6H2BFxbubS3SpHyaeSSKNsBrhCQYkrHEeiDNi6HQh5lO7chifaKsuR/NfAJDk5x6wwRgR4aFYB19NHxACCHlQoEbK7vuA83NXwFLnSoC4aLSZZbGfsweoiUgBc4AJfZYWzWopB40IhmlZ37bs94X9VlIEA+TdCanMyMPepBzC7iBRnHgW6V",
+    ),
+    (
+        "s.AAAf.AACDZZbjHHZW76AiRbJTMtzj",
+        "s.AAByG8WvOXOiHCI/PiaiL0CtDr23FUv9LV14lVYQOpEK7reOhVEHzDmRLpXbpH9wJAi3cJiVO170vtDwkQ6GYqr4F3uNtuBmPz/BpkY/qizi9EJHkDl8UndttAMz/4IM7kUlFyWy4B4Jvm4kAicOiaeIheyN+NnDXp3PpGif+l7nMZ8Sl+wYLS2TX+Q1e0Gvq/Zj2X2+U99vKH6rAJCZefKQ",
+    ),
+    (
+        "s.AABk.AADPwwMEZd+8vjgXIoyFN7Ij",
+        "s.AAB/UJxed8EWANO3PleVa6ZSYFXuWMVNHHDqezedMRG4LAenfbrJCHeiT9Ap+wgw3E4JZ/T7gCYpqgFl0fJDfZziothe1A4PFJ7qNVj3OV4qdTlI2x+6qwmt2ocZRE4WNTox8AIFecCIwp749GYAn5jyYw8nwy8TWVdbwUsgqV9IMDMXeob7xXq+I5dghHyB1YyXxVpv9pqZCD7CXi3S2fio",
+    ),
+]
+
+
+def decode_number(enc):
+    return int.from_bytes(base64.b64decode(enc), "big")
+
+
+def decode_challenge(enc):
+    dec = enc.split(".")
+    assert dec[0] == "s"
+    return [decode_number(x) for x in dec[1:]]
+
+
+def reference_solve(chal):
+    """The sloth root from pow.py."""
+    diff, x = decode_challenge(chal)
+    exponent = (MODULUS + 1) // 4
+    for _ in range(diff):
+        x = pow(x, exponent, MODULUS) ^ 1
+    return x
+
+
+def reference_verify(chal, sol):
+    """The sloth square from pow.py."""
+    diff, x = decode_challenge(chal)
+    (y,) = decode_challenge(sol)
+    for _ in range(diff):
+        y = pow(y ^ 1, 2, MODULUS)
+    return x == y or MODULUS - x == y
+
+
+class TestKctfPow(unittest.TestCase):
+    def test_solve_matches_vectors(self):
+        for chal, sol in VECTORS:
+            with self.subTest(chal=chal):
+                self.assertEqual(decode_challenge(kctf_pow.solve_challenge(chal)), decode_challenge(sol))
+
+    def test_verify_vectors(self):
+        for i, (chal, sol) in enumerate(VECTORS):
+            with self.subTest(chal=chal):
+                self.assertTrue(kctf_pow.verify_challenge(chal, sol))
+                other_sol = VECTORS[(i + 1) % len(VECTORS)][1]
+                self.assertFalse(kctf_pow.verify_challenge(chal, other_sol))
+
+    def test_generated_challenges(self):
+        for diff in [1, 10, 50]:
+            chal = kctf_pow.get_challenge(diff)
+            self.assertEqual(decode_challenge(chal)[0], diff)
+            self.assertLess(decode_challenge(chal)[1], 2**128)
+            sol = kctf_pow.solve_challenge(chal)
+            self.assertEqual(decode_challenge(sol), [reference_solve(chal)])
+            self.assertTrue(reference_verify(chal, sol))
+            self.assertTrue(kctf_pow.verify_challenge(chal, sol, allow_bypass=False))
+
+    def test_invalid(self):
+        chal, sol = VECTORS[0]
+        with self.assertRaises(ValueError):
+            kctf_pow.solve_challenge("t" + chal[1:])
+        with self.assertRaises(ValueError):
+            kctf_pow.verify_challenge(chal, "t" + sol[1:])
+        with self.assertRaises(ValueError):
+            kctf_pow.verify_challenge(chal, sol + ".AAAA")
+        self.assertFalse(kctf_pow.verify_challenge(chal, "s.asdf"))
+
+
+if __name__ == "__main__":
+    unittest.main()
@@ -0,0 +1,128 @@
+//! Solving challenges on a [`tokio`] blocking thread so that async runtimes aren't stalled.
+//!
+//! ```rust
+//! use kctf_pow::KctfPow;
+//!
+//! # tokio::runtime::Builder::new_current_thread().build().unwrap().block_on(async {
+//! let pow = KctfPow::new();
+//! let chall = pow.decode_challenge("s.AAAAMg==.H+fPiuL32DPbfN97cpd0nA==").unwrap();
+//! // the solve runs in the background until it's awaited or the handle is dropped
+//! let handle = chall.clone().spawn_solve();
+//! let sol = handle.await.unwrap();
+//! assert!(chall.check_solution(&sol));
+//! # });
+//! ```
+
+use crate::{Cancelled, Challenge, ChallengeParams, KctfPow, Solution, SolveState};
+use std::future::Future;
+use std::ops::ControlFlow;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+use std::sync::Arc;
+use std::task::{Context, Poll};
+use tokio::task::JoinHandle;
+
+/// A handle to a solve running on a blocking thread.
+///
+/// Awaiting the handle gives the solution. Dropping the handle or calling [`SolveHandle::cancel`] stops the solve,
+/// in which case awaiting it gives [`Cancelled`]. Awaiting it also gives [`Cancelled`] if the runtime shuts down before the solve starts.
+#[derive(Debug)]
+pub struct SolveHandle {
+    task: JoinHandle<Result<Solution, Cancelled>>,
+    cancelled: Arc<AtomicBool>,
+    done: Arc<AtomicU32>,
+    total: u32,
+}
+
+impl SolveHandle {
+    /// Starts solving a challenge on a blocking thread.
+    ///
+    /// This must be called from within a [`tokio`] runtime.
+    pub(crate) fn spawn(params: ChallengeParams, pow: KctfPow) -> Self {
+        let cancelled = Arc::new(AtomicBool::new(false));
+        let done = Arc::new(AtomicU32::new(0));
+        let total = params.difficulty;
+        let task = {
+            let cancelled = Arc::clone(&cancelled);
+            let done = Arc::clone(&done);
+            tokio::task::spawn_blocking(move || {
+                SolveState::new(params).solve_with(&pow, 1, |cur, _| {
+                    done.store(cur, Ordering::Relaxed);
+                    if cancelled.load(Ordering::Relaxed) {
+                        ControlFlow::Break(())
+                    } else {
+                        ControlFlow::Continue(())
+                    }
+                })
+            })
+        };
+        Self {
+            task,
+            cancelled,
+            done,
+            total,
+        }
+    }
+
+    /// Stops the solve as soon as the current iteration finishes.
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+    }
+
+    /// Returns the number of iterations done so far and the total number of iterations.
+    pub fn progress(&self) -> (u32, u32) {
+        (self.done.load(Ordering::Relaxed), self.total)
+    }
+}
+
+impl Future for SolveHandle {
+    type Output = Result<Solution, Cancelled>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        Pin::new(&mut self.task).poll(cx).map(|res| match res {
+            Ok(res) => res,
+            // the task is only cancelled if the runtime shuts down before it starts
+            Err(err) if err.is_cancelled() => Err(Cancelled),
+            Err(err) => std::panic::resume_unwind(err.into_panic()),
+        })
+    }
+}
+
+impl Drop for SolveHandle {
+    fn drop(&mut self) {
+        self.cancel();
+    }
+}
+
+impl ChallengeParams {
+    /// Starts solving a challenge given a proof-of-work system on a blocking thread and returns a handle to the solve.
+    ///
+    /// This must be called from within a [`tokio`] runtime.
+    pub fn spawn_solve(self, pow: &KctfPow) -> SolveHandle {
+        SolveHandle::spawn(self, pow.clone())
+    }
+
+    /// Solves a challenge given a proof-of-work system on a blocking thread and returns the solution.
+    ///
+    /// Dropping the future stops the solve. Returns [`Cancelled`] if the runtime shuts down before the solve starts.
+    /// This must be called from within a [`tokio`] runtime.
+    pub async fn solve_async(self, pow: &KctfPow) -> Result<Solution, Cancelled> {
+        self.spawn_solve(pow).await
+    }
+}
+
+impl<'a> Challenge<'a> {
+    /// Starts solving a challenge on a blocking thread and returns a handle to the solve.
+    ///
+    /// This must be called from within a [`tokio`] runtime.
+    pub fn spawn_solve(self) -> SolveHandle {
+        self.params.spawn_solve(self.pow)
+    }
+
+    /// Solves a challenge on a blocking thread and returns the solution.
+    ///
+    /// See [`ChallengeParams::solve_async`] for when [`Cancelled`] is returned. This must be called from within a [`tokio`] runtime.
+    pub async fn solve_async(self) -> Result<Solution, Cancelled> {
+        self.params.solve_async(self.pow).await
+    }
+}
@@ -0,0 +1,93 @@
+//! Solving many challenges at once on worker threads, and checking many solutions at once,
+//! spread across a [`rayon`](https://docs.rs/rayon) thread pool when the `rayon` feature is enabled.
+
+#[cfg(feature = "std")]
+use crate::Solution;
+use crate::{ChallengeParams, KctfPow, PowError, Verifier};
+use alloc::vec::Vec;
+#[cfg(feature = "std")]
+use core::sync::atomic::{AtomicUsize, Ordering};
+#[cfg(feature = "rayon")]
+use rayon::prelude::*;
+
+impl KctfPow {
+    /// Solves many challenges at once on `threads` worker threads, and returns their solutions in the same order.
+    ///
+    /// Each challenge is still solved by a single thread, so this is only faster than solving them one after another when there are multiple challenges.
+    /// A `threads` of 0 uses as many threads as [`std::thread::available_parallelism`] suggests.
+    #[cfg(feature = "std")]
+    pub fn solve_many(&self, challs: &[ChallengeParams], threads: usize) -> Vec<Solution> {
+        let threads = match threads {
+            0 => std::thread::available_parallelism().map_or(1, |x| x.get()),
+            threads => threads,
+        };
+        let next = AtomicUsize::new(0);
+        let mut sols = vec![None; challs.len()];
+        std::thread::scope(|scope| {
+            let workers: Vec<_> = (0..threads.min(challs.len()))
+                .map(|_| {
+                    scope.spawn(|| {
+                        // each worker takes the next unsolved challenge until there are none left
+                        let mut solved = Vec::new();
+                        loop {
+                            let i = next.fetch_add(1, Ordering::Relaxed);
+                            match challs.get(i) {
+                                Some(chall) => solved.push((i, chall.clone().solve(self))),
+                                None => return solved,
+                            }
+                        }
+                    })
+                })
+                .collect();
+            for worker in workers {
+                let solved = worker
+                    .join()
+                    .unwrap_or_else(|err| std::panic::resume_unwind(err));
+                for (i, sol) in solved {
+                    sols[i] = Some(sol);
+                }
+            }
+        });
+        sols.into_iter()
+            .map(|sol| sol.expect("every challenge should have been solved"))
+            .collect()
+    }
+
+    /// Checks a batch of solutions against their challenges, and returns the result of each check in the same order.
+    ///
+    /// With the `rayon` feature, the checks are spread across the current rayon thread pool, which is the global pool
+    /// unless this is called from inside another pool (see [`KctfPow::check_batch_in`]). Without it, they're checked one after another.
+    /// Either way, each result is the same as what [`ChallengeParams::check`] would return.
+    pub fn check_batch(&self, checks: &[(ChallengeParams, &str)]) -> Vec<Result<bool, PowError>> {
+        #[cfg(feature = "rayon")]
+        {
+            checks
+                .par_iter()
+                .map_init(
+                    || Verifier::new(self),
+                    |verifier, (params, sol)| verifier.check(params, sol),
+                )
+                .collect()
+        }
+        #[cfg(not(feature = "rayon"))]
+        {
+            let mut verifier = Verifier::new(self);
+            checks
+                .iter()
+                .map(|(params, sol)| verifier.check(params, sol))
+                .collect()
+        }
+    }
+
+    /// Checks a batch of solutions against their challenges on a specific thread pool, and returns the result of each check in the same order.
+    ///
+    /// See [`KctfPow::check_batch`] for what each result is.
+    #[cfg(feature = "rayon")]
+    pub fn check_batch_in(
+        &self,
+        pool: &rayon::ThreadPool,
+        checks: &[(ChallengeParams, &str)],
+    ) -> Vec<Result<bool, PowError>> {
+        pool.install(|| self.check_batch(checks))
+    }
+}
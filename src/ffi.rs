@@ -0,0 +1,261 @@
+//! A C ABI for embedding in services that aren't written in Rust. The header is at `ffi/kctf_pow.h`.
+//!
+//! Every object and string returned by these functions is owned by the caller, and must be freed with the matching
+//! `kctf_pow_*_free` function rather than `free`. Strings passed in are only borrowed for the duration of the call,
+//! and must be null-terminated UTF-8.
+
+use crate::{ChallengeParams, KctfPow, PowError};
+use alloc::boxed::Box;
+use alloc::ffi::CString;
+use alloc::string::{String, ToString};
+use core::ffi::{c_char, CStr};
+use core::ptr;
+
+/// The result of a call that can fail.
+///
+/// Errors have the same values as their [`PowError::code`], which is also the exit code of the CLI.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum KctfPowStatus {
+    /// The call succeeded.
+    Ok = 0,
+    /// A pointer argument was null, or a string argument wasn't valid UTF-8.
+    InvalidArgument = 1,
+    /// The version prefix isn't the one this library supports.
+    WrongVersion = 2,
+    /// The input doesn't have the right number of `.`-separated parts.
+    WrongPartCount = 3,
+    /// A part isn't valid base64.
+    InvalidBase64 = 4,
+    /// The difficulty doesn't fit in 32 bits.
+    DifficultyTooLarge = 5,
+    /// A value is outside of the range it's allowed to be in.
+    ValueOutOfRange = 6,
+    /// The input ended before all of its data was read.
+    Truncated = 7,
+    /// The input isn't in a form that this library or kCTF encodes it in.
+    NonCanonical = 8,
+    /// The input is longer than any valid input could be.
+    TooLong = 9,
+    /// An authentication tag doesn't match.
+    AuthenticationFailed = 10,
+    /// The modulus isn't valid.
+    InvalidModulus = 11,
+}
+
+impl From<PowError> for KctfPowStatus {
+    fn from(err: PowError) -> Self {
+        match err {
+            PowError::WrongVersion(_) => KctfPowStatus::WrongVersion,
+            PowError::WrongPartCount => KctfPowStatus::WrongPartCount,
+            PowError::InvalidBase64 { .. } => KctfPowStatus::InvalidBase64,
+            PowError::DifficultyTooLarge => KctfPowStatus::DifficultyTooLarge,
+            PowError::ValueOutOfRange => KctfPowStatus::ValueOutOfRange,
+            PowError::Truncated => KctfPowStatus::Truncated,
+            PowError::NonCanonical => KctfPowStatus::NonCanonical,
+            PowError::TooLong => KctfPowStatus::TooLong,
+            PowError::AuthenticationFailed => KctfPowStatus::AuthenticationFailed,
+            PowError::InvalidModulus => KctfPowStatus::InvalidModulus,
+        }
+    }
+}
+
+/// The parameters of a challenge, which don't depend on the proof-of-work system that solves or checks them.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct KctfPowChallenge(ChallengeParams);
+
+/// Borrows a C string as a `&str`, returning `None` if it's null or isn't valid UTF-8.
+///
+/// # Safety
+///
+/// `string` must be null or point to a null-terminated string that outlives `'a`.
+unsafe fn borrow_str<'a>(string: *const c_char) -> Option<&'a str> {
+    if string.is_null() {
+        return None;
+    }
+    CStr::from_ptr(string).to_str().ok()
+}
+
+/// Converts a string into an owned C string, which is freed with [`kctf_pow_string_free`].
+fn into_c_string(string: String) -> *mut c_char {
+    // the encodings only use base64 and `.`, so they never contain a null byte
+    CString::new(string)
+        .expect("encoded string contains a null byte")
+        .into_raw()
+}
+
+/// Creates a proof-of-work system using kCTF's modulus. It must be freed with `kctf_pow_free`.
+#[no_mangle]
+pub extern "C" fn kctf_pow_new() -> *mut KctfPow {
+    Box::into_raw(Box::new(KctfPow::new()))
+}
+
+/// Frees a proof-of-work system. Passing null does nothing.
+///
+/// # Safety
+///
+/// `pow` must be null or have been returned by `kctf_pow_new`, and must not be used afterwards.
+#[no_mangle]
+pub unsafe extern "C" fn kctf_pow_free(pow: *mut KctfPow) {
+    if !pow.is_null() {
+        drop(Box::from_raw(pow));
+    }
+}
+
+/// Decodes a challenge from a string.
+///
+/// On success, `*out` is set to the challenge, which must be freed with `kctf_pow_challenge_free`.
+/// On failure, `*out` is left unchanged.
+///
+/// # Safety
+///
+/// `chall` must be null or a null-terminated string, and `out` must be null or valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn kctf_pow_decode_challenge(
+    chall: *const c_char,
+    out: *mut *mut KctfPowChallenge,
+) -> KctfPowStatus {
+    let chall = match borrow_str(chall) {
+        Some(chall) if !out.is_null() => chall,
+        _ => return KctfPowStatus::InvalidArgument,
+    };
+    match ChallengeParams::decode_challenge(chall) {
+        Ok(params) => {
+            *out = Box::into_raw(Box::new(KctfPowChallenge(params)));
+            KctfPowStatus::Ok
+        }
+        Err(err) => err.into(),
+    }
+}
+
+/// Generates a random challenge given a difficulty. It must be freed with `kctf_pow_challenge_free`.
+#[no_mangle]
+pub extern "C" fn kctf_pow_generate_challenge(difficulty: u32) -> *mut KctfPowChallenge {
+    Box::into_raw(Box::new(KctfPowChallenge(
+        ChallengeParams::generate_challenge(difficulty),
+    )))
+}
+
+/// Returns the difficulty of a challenge, or 0 if `chall` is null.
+///
+/// # Safety
+///
+/// `chall` must be null or a live challenge.
+#[no_mangle]
+pub unsafe extern "C" fn kctf_pow_challenge_difficulty(chall: *const KctfPowChallenge) -> u32 {
+    chall.as_ref().map_or(0, |chall| chall.0.difficulty)
+}
+
+/// Encodes a challenge into a string, which must be freed with `kctf_pow_string_free`. Returns null if `chall` is null.
+///
+/// # Safety
+///
+/// `chall` must be null or a live challenge.
+#[no_mangle]
+pub unsafe extern "C" fn kctf_pow_challenge_to_string(
+    chall: *const KctfPowChallenge,
+) -> *mut c_char {
+    match chall.as_ref() {
+        Some(chall) => into_c_string(chall.0.to_string()),
+        None => ptr::null_mut(),
+    }
+}
+
+/// Frees a challenge. Passing null does nothing.
+///
+/// # Safety
+///
+/// `chall` must be null or have been returned by this library, and must not be used afterwards.
+#[no_mangle]
+pub unsafe extern "C" fn kctf_pow_challenge_free(chall: *mut KctfPowChallenge) {
+    if !chall.is_null() {
+        drop(Box::from_raw(chall));
+    }
+}
+
+/// Solves a challenge and returns the solution as a string, which must be freed with `kctf_pow_string_free`.
+/// Returns null if either argument is null.
+///
+/// # Safety
+///
+/// `pow` and `chall` must each be null or live.
+#[no_mangle]
+pub unsafe extern "C" fn kctf_pow_solve(
+    pow: *const KctfPow,
+    chall: *const KctfPowChallenge,
+) -> *mut c_char {
+    match (pow.as_ref(), chall.as_ref()) {
+        (Some(pow), Some(chall)) => into_c_string(chall.0.clone().solve(pow).to_string()),
+        _ => ptr::null_mut(),
+    }
+}
+
+/// Checks a solution string to see if it satisfies a challenge.
+///
+/// On success, `*valid` is set to whether the solution is correct. A solution that can't be decoded is an error
+/// rather than an incorrect solution, in which case `*valid` is left unchanged.
+///
+/// # Safety
+///
+/// `pow` and `chall` must each be null or live, `sol` must be null or a null-terminated string,
+/// and `valid` must be null or valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn kctf_pow_check(
+    pow: *const KctfPow,
+    chall: *const KctfPowChallenge,
+    sol: *const c_char,
+    valid: *mut bool,
+) -> KctfPowStatus {
+    let (pow, chall, sol) = match (pow.as_ref(), chall.as_ref(), borrow_str(sol)) {
+        (Some(pow), Some(chall), Some(sol)) if !valid.is_null() => (pow, chall, sol),
+        _ => return KctfPowStatus::InvalidArgument,
+    };
+    match chall.0.check(pow, sol) {
+        Ok(res) => {
+            *valid = res;
+            KctfPowStatus::Ok
+        }
+        Err(err) => err.into(),
+    }
+}
+
+/// Frees a string returned by this library. Passing null does nothing.
+///
+/// # Safety
+///
+/// `string` must be null or have been returned by this library, and must not be used afterwards.
+#[no_mangle]
+pub unsafe extern "C" fn kctf_pow_string_free(string: *mut c_char) {
+    if !string.is_null() {
+        drop(CString::from_raw(string));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn status_matches_code() {
+        let errs = [
+            PowError::WrongVersion("t".into()),
+            PowError::WrongPartCount,
+            PowError::InvalidBase64 { part: 1 },
+            PowError::DifficultyTooLarge,
+            PowError::ValueOutOfRange,
+            PowError::Truncated,
+            PowError::NonCanonical,
+            PowError::TooLong,
+            PowError::AuthenticationFailed,
+            PowError::InvalidModulus,
+        ];
+        for err in errs {
+            assert_eq!(
+                KctfPowStatus::from(err.clone()) as u8,
+                err.code(),
+                "converting {:?}",
+                err
+            );
+        }
+    }
+}
@@ -0,0 +1,265 @@
+//! A hashcash scheme, where solving means finding a SHA-256 hash with enough leading zero bits.
+//!
+//! Checking a solution takes a single hash, and [`HashcashParams::solve_parallel`] splits solving across threads by giving each one different counters to try.
+
+use crate::{decode_parts, decode_u32, decode_u64, PowError, PowScheme, DEFAULT_VALUE_LEN};
+use alloc::string::{String, ToString};
+use alloc::vec;
+use alloc::vec::Vec;
+use base64::prelude::*;
+use core::fmt;
+use core::str::FromStr;
+#[cfg(feature = "std")]
+use core::sync::atomic::{AtomicU64, Ordering};
+use rand::prelude::*;
+use sha2::{Digest, Sha256};
+
+const VERSION: &str = "h";
+
+/// The SHA-256 hashcash proof-of-work scheme.
+///
+/// The difficulty of a challenge is the number of leading zero bits that the hash of its value and the solution's counter must have.
+/// Each extra bit doubles the expected time to solve.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Hashcash;
+
+/// The parameters for a hashcash challenge.
+///
+/// If you want to serialize it to a string, use the [`Display`](core::fmt::Display) implementation.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct HashcashParams {
+    /// The number of leading zero bits that the hash must have, which is at most [`Hashcash::MAX_DIFFICULTY`].
+    pub difficulty: u32,
+    /// The random value that is hashed along with the counter.
+    pub val: Vec<u8>,
+}
+
+/// The solution to a hashcash challenge.
+///
+/// If you want to serialize it to a string, use the [`Display`](core::fmt::Display) implementation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct HashcashSolution {
+    /// The counter that is hashed along with the challenge's value.
+    pub counter: u64,
+}
+
+impl Hashcash {
+    /// The largest possible difficulty, which is the number of bits in a SHA-256 hash.
+    pub const MAX_DIFFICULTY: u32 = 256;
+}
+
+impl HashcashParams {
+    /// Decodes a challenge from a string and returns it.
+    ///
+    /// Returns [`PowError::DifficultyTooLarge`] if the difficulty is above [`Hashcash::MAX_DIFFICULTY`].
+    pub fn decode_challenge(chall_string: &str) -> Result<HashcashParams, PowError> {
+        let decoded_data = decode_parts(chall_string, VERSION, 2)?;
+        let difficulty = decode_u32(&decoded_data[0], PowError::DifficultyTooLarge)?;
+        if difficulty > Hashcash::MAX_DIFFICULTY {
+            return Err(PowError::DifficultyTooLarge);
+        }
+        Ok(Self {
+            difficulty,
+            val: decoded_data[1].clone(),
+        })
+    }
+
+    /// Generates a random challenge given a difficulty, using a specific random number generator.
+    ///
+    /// Returns [`PowError::DifficultyTooLarge`] if the difficulty is above [`Hashcash::MAX_DIFFICULTY`].
+    pub fn generate_challenge_with_rng<R: CryptoRng + RngCore>(
+        rng: &mut R,
+        difficulty: u32,
+    ) -> Result<HashcashParams, PowError> {
+        if difficulty > Hashcash::MAX_DIFFICULTY {
+            return Err(PowError::DifficultyTooLarge);
+        }
+        let mut val = vec![0; DEFAULT_VALUE_LEN];
+        rng.fill_bytes(&mut val);
+        Ok(Self { difficulty, val })
+    }
+
+    /// Solves a challenge and returns the solution.
+    ///
+    /// # Panics
+    ///
+    /// Panics if no counter works, which is only likely for difficulties far too large to solve anyways.
+    pub fn solve(&self) -> HashcashSolution {
+        (0..=u64::MAX)
+            .map(|counter| HashcashSolution { counter })
+            .find(|sol| sol.check(self))
+            .expect("no counter satisfies the challenge")
+    }
+
+    /// Solves a challenge on `threads` threads and returns the solution, which is the same one that [`HashcashParams::solve`] finds.
+    ///
+    /// Each thread tries every `threads`th counter. A `threads` of 0 uses as many threads as [`std::thread::available_parallelism`] suggests.
+    ///
+    /// # Panics
+    ///
+    /// Panics if no counter works, which is only likely for difficulties far too large to solve anyways.
+    #[cfg(feature = "std")]
+    pub fn solve_parallel(&self, threads: usize) -> HashcashSolution {
+        let threads = match threads {
+            0 => std::thread::available_parallelism().map_or(1, |x| x.get()),
+            threads => threads,
+        } as u64;
+        // the smallest counter found so far, which every thread stops at so that the smallest one overall is found
+        let best = AtomicU64::new(u64::MAX);
+        std::thread::scope(|scope| {
+            for first in 0..threads {
+                let best = &best;
+                scope.spawn(move || {
+                    let mut counter = first;
+                    while counter < best.load(Ordering::Relaxed) {
+                        if (HashcashSolution { counter }).check(self) {
+                            best.fetch_min(counter, Ordering::Relaxed);
+                            return;
+                        }
+                        counter = match counter.checked_add(threads) {
+                            Some(counter) => counter,
+                            None => return,
+                        };
+                    }
+                });
+            }
+        });
+        // no thread tries u64::MAX itself, since it's where they all start out stopping
+        let sol = HashcashSolution {
+            counter: best.into_inner(),
+        };
+        assert!(sol.check(self), "no counter satisfies the challenge");
+        sol
+    }
+}
+
+impl HashcashSolution {
+    /// Decodes a solution from a string and returns it.
+    pub fn decode_solution(sol_string: &str) -> Result<HashcashSolution, PowError> {
+        let decoded_data = decode_parts(sol_string, VERSION, 1)?;
+        Ok(Self {
+            counter: decode_u64(&decoded_data[0], PowError::ValueOutOfRange)?,
+        })
+    }
+
+    /// Checks the solution to see if it satisfies a challenge.
+    pub fn check(&self, params: &HashcashParams) -> bool {
+        let mut hasher = Sha256::new();
+        hasher.update(&params.val);
+        hasher.update(self.counter.to_be_bytes());
+        leading_zero_bits(&hasher.finalize()) >= params.difficulty
+    }
+}
+
+impl PowScheme for Hashcash {
+    type Challenge = HashcashParams;
+    type Solution = HashcashSolution;
+
+    const VERSION: &'static str = VERSION;
+
+    fn generate_with_rng<R: CryptoRng + RngCore>(
+        &self,
+        rng: &mut R,
+        difficulty: u32,
+    ) -> Result<HashcashParams, PowError> {
+        HashcashParams::generate_challenge_with_rng(rng, difficulty)
+    }
+
+    fn encode_challenge(&self, chall: &HashcashParams) -> String {
+        chall.to_string()
+    }
+
+    fn decode_challenge(&self, chall_string: &str) -> Result<HashcashParams, PowError> {
+        HashcashParams::decode_challenge(chall_string)
+    }
+
+    fn encode_solution(&self, sol: &HashcashSolution) -> String {
+        sol.to_string()
+    }
+
+    fn decode_solution(&self, sol_string: &str) -> Result<HashcashSolution, PowError> {
+        HashcashSolution::decode_solution(sol_string)
+    }
+
+    fn solve(&self, chall: &HashcashParams) -> HashcashSolution {
+        chall.solve()
+    }
+
+    fn check(&self, chall: &HashcashParams, sol: &HashcashSolution) -> bool {
+        sol.check(chall)
+    }
+}
+
+/// Counts the leading zero bits of a big-endian byte string.
+fn leading_zero_bits(bytes: &[u8]) -> u32 {
+    let mut count = 0;
+    for &byte in bytes {
+        count += byte.leading_zeros();
+        if byte != 0 {
+            break;
+        }
+    }
+    count
+}
+
+impl fmt::Display for HashcashParams {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            fmt,
+            "{}.{}.{}",
+            VERSION,
+            BASE64_STANDARD.encode(self.difficulty.to_be_bytes()),
+            BASE64_STANDARD.encode(&self.val)
+        )
+    }
+}
+
+impl fmt::Display for HashcashSolution {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            fmt,
+            "{}.{}",
+            VERSION,
+            BASE64_STANDARD.encode(self.counter.to_be_bytes())
+        )
+    }
+}
+
+impl FromStr for HashcashParams {
+    type Err = PowError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::decode_challenge(s)
+    }
+}
+
+impl FromStr for HashcashSolution {
+    type Err = PowError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::decode_solution(s)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn counts_leading_zero_bits() {
+        let cases: [(&[u8], u32); 9] = [
+            (&[], 0),
+            (&[0x80], 0),
+            (&[0x01], 7),
+            (&[0x00], 8),
+            (&[0x00, 0x80], 8),
+            (&[0x00, 0x01], 15),
+            (&[0x00, 0x00], 16),
+            (&[0x00, 0x00, 0xff, 0x00], 16),
+            (&[0x00; 32], 256),
+        ];
+        for (bytes, zeros) in cases {
+            assert_eq!(leading_zero_bits(bytes), zeros, "counting {:?}", bytes);
+        }
+    }
+}
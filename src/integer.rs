@@ -0,0 +1,313 @@
+//! Big integer backend selection.
+//!
+//! The `gmp` feature (enabled by default) uses [`rug`], while the `pure-rust` feature uses [`num_bigint`] so that no C libraries need to be built.
+//! Everything else in the crate only goes through [`Integer`] and the functions in this module, so both backends produce byte-identical challenges and solutions.
+//!
+//! [`Integer`] wraps the backend's own type instead of being it, so the features are additive: code written against it compiles with either backend,
+//! and `gmp` is used when both are enabled.
+
+use alloc::vec;
+use alloc::vec::Vec;
+use core::fmt;
+use core::ops::{
+    Add, AddAssign, BitOr, BitOrAssign, Div, DivAssign, Mul, MulAssign, Rem, RemAssign, Shl, Shr,
+    Sub, SubAssign,
+};
+use rand::RngCore;
+
+#[cfg(not(any(feature = "gmp", feature = "pure-rust")))]
+compile_error!("either the `gmp` or the `pure-rust` feature must be enabled");
+
+/// An arbitrary-precision unsigned integer.
+///
+/// This is backed by `rug::Integer` with the `gmp` feature and `num_bigint::BigUint` with `pure-rust`,
+/// and only has the operations that behave the same with both. Like with the primitive unsigned integers, subtracting below zero panics.
+#[derive(Clone, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Integer(backend::Inner);
+
+#[cfg(feature = "gmp")]
+mod backend {
+    use super::Integer;
+    use alloc::vec::Vec;
+    use rug::integer::Order;
+    use rug::ops::Pow;
+
+    pub type Inner = rug::Integer;
+
+    /// Wraps the result of an operation, checking that it didn't go below zero since `rug` integers are signed.
+    pub fn unsigned(val: Inner) -> Integer {
+        assert!(val >= 0, "attempt to subtract with overflow");
+        Integer(val)
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Integer {
+        Integer(Inner::from_digits(bytes, Order::Msf))
+    }
+
+    pub fn to_bytes(val: &Integer) -> Vec<u8> {
+        val.0.to_digits(Order::Msf)
+    }
+
+    pub fn mersenne(exp: u32) -> Integer {
+        Integer(Inner::from(2).pow(exp) - 1)
+    }
+
+    pub fn pow_mod(val: &mut Integer, exponent: &Integer, modulus: &Integer) {
+        // guaranteed to succeed so ignore the result
+        let _ = val.0.pow_mod_mut(&exponent.0, &modulus.0);
+    }
+
+    pub fn square_mod(val: &mut Integer, modulus: &Integer) {
+        val.0.square_mut();
+        val.0 %= &modulus.0;
+    }
+
+    pub fn mul_mod(a: &Integer, b: &Integer, modulus: &Integer) -> Integer {
+        Integer(Inner::from(&a.0 * &b.0) % &modulus.0)
+    }
+
+    pub fn is_odd(val: &Integer) -> bool {
+        val.0.is_odd()
+    }
+
+    pub fn flip_low_bit(val: &mut Integer) {
+        val.0 ^= 1;
+    }
+
+    pub fn reduce(val: &Integer, modulus: &Integer) -> Integer {
+        Integer(Inner::from(&val.0 % &modulus.0))
+    }
+
+    pub fn negate_mod(val: &Integer, modulus: &Integer) -> Option<Integer> {
+        if val > modulus {
+            None
+        } else {
+            Some(Integer(Inner::from(&modulus.0 - &val.0)))
+        }
+    }
+
+    pub fn to_limbs(val: &Integer, limbs: &mut [u64]) -> bool {
+        if val.0.significant_digits::<u64>() > limbs.len() {
+            return false;
+        }
+        // pads the rest of the limbs with zeros
+        val.0.write_digits(limbs, Order::Lsf);
+        true
+    }
+}
+
+#[cfg(all(feature = "pure-rust", not(feature = "gmp")))]
+mod backend {
+    use super::Integer;
+    use alloc::vec::Vec;
+
+    pub type Inner = num_bigint::BigUint;
+
+    /// Wraps the result of an operation, which `num-bigint` already checks didn't go below zero.
+    pub fn unsigned(val: Inner) -> Integer {
+        Integer(val)
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Integer {
+        Integer(Inner::from_bytes_be(bytes))
+    }
+
+    pub fn to_bytes(val: &Integer) -> Vec<u8> {
+        // num-bigint encodes zero as a single byte, but rug encodes it as nothing
+        if val.0.bits() == 0 {
+            Vec::new()
+        } else {
+            val.0.to_bytes_be()
+        }
+    }
+
+    pub fn mersenne(exp: u32) -> Integer {
+        Integer((Inner::from(1u32) << exp) - 1u32)
+    }
+
+    pub fn pow_mod(val: &mut Integer, exponent: &Integer, modulus: &Integer) {
+        val.0 = val.0.modpow(&exponent.0, &modulus.0);
+    }
+
+    pub fn square_mod(val: &mut Integer, modulus: &Integer) {
+        val.0 = &val.0 * &val.0 % &modulus.0;
+    }
+
+    pub fn mul_mod(a: &Integer, b: &Integer, modulus: &Integer) -> Integer {
+        Integer(&a.0 * &b.0 % &modulus.0)
+    }
+
+    pub fn is_odd(val: &Integer) -> bool {
+        val.0.bit(0)
+    }
+
+    pub fn flip_low_bit(val: &mut Integer) {
+        let bit = val.0.bit(0);
+        val.0.set_bit(0, !bit);
+    }
+
+    pub fn reduce(val: &Integer, modulus: &Integer) -> Integer {
+        Integer(&val.0 % &modulus.0)
+    }
+
+    pub fn negate_mod(val: &Integer, modulus: &Integer) -> Option<Integer> {
+        if val > modulus {
+            None
+        } else {
+            Some(Integer(&modulus.0 - &val.0))
+        }
+    }
+
+    pub fn to_limbs(val: &Integer, limbs: &mut [u64]) -> bool {
+        if val.0.bits() > limbs.len() as u64 * 64 {
+            return false;
+        }
+        limbs.fill(0);
+        for (limb, digit) in limbs.iter_mut().zip(val.0.iter_u64_digits()) {
+            *limb = digit;
+        }
+        true
+    }
+}
+
+pub(crate) use backend::{
+    flip_low_bit, from_bytes, is_odd, mersenne, mul_mod, negate_mod, pow_mod, reduce, square_mod,
+    to_bytes, to_limbs,
+};
+
+impl Integer {
+    /// Creates an integer from its big-endian bytes.
+    pub fn from_bytes_be(bytes: &[u8]) -> Integer {
+        from_bytes(bytes)
+    }
+
+    /// Returns the big-endian bytes of the integer, which are empty for zero.
+    pub fn to_bytes_be(&self) -> Vec<u8> {
+        to_bytes(self)
+    }
+
+    /// Returns whether the integer is odd.
+    pub fn is_odd(&self) -> bool {
+        is_odd(self)
+    }
+
+    /// Returns the integer raised to `exponent` modulo `modulus`.
+    pub fn pow_mod(&self, exponent: &Integer, modulus: &Integer) -> Integer {
+        let mut val = self.clone();
+        pow_mod(&mut val, exponent, modulus);
+        val
+    }
+}
+
+macro_rules! impl_from {
+    ($($ty:ty),*) => {
+        $(
+            impl From<$ty> for Integer {
+                fn from(val: $ty) -> Self {
+                    Integer(val.into())
+                }
+            }
+        )*
+    };
+}
+
+impl_from!(u8, u16, u32, u64, u128);
+
+impl fmt::Debug for Integer {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&self.0, fmt)
+    }
+}
+
+impl fmt::Display for Integer {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&self.0, fmt)
+    }
+}
+
+/// Implements an operator between integers, and between an integer and a [`u32`] if the backends both support it, along with its assigning form.
+macro_rules! impl_op {
+    ($op:ident, $method:ident, $op_assign:ident, $method_assign:ident $(, $rhs:ty)*) => {
+        impl $op for Integer {
+            type Output = Integer;
+
+            fn $method(self, rhs: Integer) -> Integer {
+                backend::unsigned($op::$method(self.0, rhs.0))
+            }
+        }
+
+        impl $op<&Integer> for Integer {
+            type Output = Integer;
+
+            fn $method(self, rhs: &Integer) -> Integer {
+                backend::unsigned($op::$method(self.0, &rhs.0))
+            }
+        }
+
+        $(
+            impl $op<$rhs> for Integer {
+                type Output = Integer;
+
+                fn $method(self, rhs: $rhs) -> Integer {
+                    backend::unsigned($op::$method(self.0, rhs))
+                }
+            }
+
+            impl $op_assign<$rhs> for Integer {
+                fn $method_assign(&mut self, rhs: $rhs) {
+                    *self = $op::$method(core::mem::take(self), rhs);
+                }
+            }
+        )*
+
+        impl $op_assign for Integer {
+            fn $method_assign(&mut self, rhs: Integer) {
+                *self = $op::$method(core::mem::take(self), rhs);
+            }
+        }
+
+        impl $op_assign<&Integer> for Integer {
+            fn $method_assign(&mut self, rhs: &Integer) {
+                *self = $op::$method(core::mem::take(self), rhs);
+            }
+        }
+    };
+}
+
+impl_op!(Add, add, AddAssign, add_assign, u32);
+impl_op!(Sub, sub, SubAssign, sub_assign, u32);
+impl_op!(Mul, mul, MulAssign, mul_assign, u32);
+impl_op!(Div, div, DivAssign, div_assign, u32);
+impl_op!(Rem, rem, RemAssign, rem_assign, u32);
+impl_op!(BitOr, bitor, BitOrAssign, bitor_assign);
+
+impl Shl<u32> for Integer {
+    type Output = Integer;
+
+    fn shl(self, rhs: u32) -> Integer {
+        Integer(self.0 << rhs)
+    }
+}
+
+impl Shr<u32> for Integer {
+    type Output = Integer;
+
+    fn shr(self, rhs: u32) -> Integer {
+        Integer(self.0 >> rhs)
+    }
+}
+
+/// How many more bytes than the modulus has are reduced to get a value below it, which makes the bias from reducing negligible.
+const EXTRA_LEN: usize = 16;
+
+/// Returns how many bytes to reduce by a modulus so that the result is nearly uniform below it.
+pub(crate) fn uniform_len(modulus: &Integer) -> usize {
+    to_bytes(modulus).len() + EXTRA_LEN
+}
+
+/// Returns a random value below a modulus.
+pub(crate) fn random_below<R: RngCore>(rng: &mut R, modulus: &Integer) -> Integer {
+    let mut bytes = vec![0; uniform_len(modulus)];
+    rng.fill_bytes(&mut bytes);
+    reduce(&from_bytes(&bytes), modulus)
+}
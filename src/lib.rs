@@ -1,7 +1,7 @@
 //! A library to solve, check, and generate proof-of-work challenges using [kCTF](https://google.github.io/kctf/)'s scheme.
 //!
 //! ```rust
-//! use kctf_pow::KctfPow;
+//! use kctf_pow::{KctfPow, Solution};
 //!
 //! let pow = KctfPow::new();
 //! // decoding then solving a challenge
@@ -12,36 +12,124 @@
 //! let sol = "s.NUH3arymnKB+ysUGdv+67ypDamn4wOKCPORB2ivWE1Yhinam2v4S6q4nAoC5LP97LScdVoq+NuFVF++Win5mNRYZS6bJAs8fk0h8XgvfcC/7JfmFISqeCIo/CIUgIucVAM+eGDjqitRULGXqIOyviJoJjW8DMouMRuJM/3eg/z18kutQHkX0N3sqPeF7Nzkk8S3Bs6aiHUORM30syUKYug==";
 //! assert_eq!(chall.check(sol), Ok(true));
 //! assert_eq!(chall.check("s.asdf"), Ok(false));
+//! // solutions can also be decoded ahead of time
+//! let sol: Solution = sol.parse().unwrap();
+//! assert!(chall.check_solution(&sol));
+//! # #[cfg(feature = "std")] {
 //! // generating a random challenge of difficulty 50
 //! let chall = pow.generate_challenge(50);
 //! println!("{}", chall);
+//! # }
 //! ```
+//!
+//! Without the `std` feature (enabled by default), the crate is `no_std` and only needs `alloc`.
+//! Methods that would use [`thread_rng`](https://docs.rs/rand/0.8/rand/fn.thread_rng.html), threads, or the shared [`KctfPow`] instance aren't available,
+//! so challenges have to be generated with an explicitly passed random number generator.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
+
+#[cfg(feature = "tokio")]
+mod async_solve;
+mod batch;
+#[cfg(feature = "ffi")]
+mod ffi;
+mod hashcash;
+mod integer;
+mod mersenne;
+mod prime;
+#[cfg(feature = "python")]
+mod python;
+mod scheme;
+#[cfg(feature = "serde")]
+mod serde_impl;
+mod sqrt;
+mod timelock;
+pub mod vdf;
+mod verifier;
+#[cfg(feature = "wasm")]
+pub mod wasm;
+mod wesolowski;
 
-use base64::prelude::*;
+#[cfg(feature = "tokio")]
+pub use async_solve::SolveHandle;
+pub use hashcash::{Hashcash, HashcashParams, HashcashSolution};
+pub use integer::Integer;
+pub use scheme::{scheme_version, PowScheme};
+#[cfg(feature = "serde")]
+pub use serde_impl::serde_structured;
+pub use timelock::TimeLock;
+pub use verifier::Verifier;
+pub use wesolowski::{DelayChallenge, DelayParams, DelaySolution, Wesolowski};
+
+use alloc::string::String;
+use alloc::vec;
+use alloc::vec::Vec;
+use base64::engine::{DecodePaddingMode, Engine, GeneralPurpose, GeneralPurposeConfig};
+use base64::{alphabet, prelude::*};
+use core::convert::TryInto;
+use core::error::Error;
+use core::fmt;
+use core::ops::ControlFlow;
+use core::str::FromStr;
+use mersenne::Mersenne1279;
 use rand::prelude::*;
-use rug::integer::Order;
-use rug::ops::Pow;
-use rug::Integer;
-use std::convert::TryInto;
-use std::fmt;
+use sqrt::Sloth;
+#[cfg(feature = "std")]
+use std::sync::OnceLock;
 
 const VERSION: &str = "s";
+/// The number of random bytes in the starting value of a generated challenge.
+const DEFAULT_VALUE_LEN: usize = 16;
+/// The number of bytes in the longest difficulty that strict decoding accepts, which is how long kCTF encodes [`u32::MAX`].
+const MAX_DIFFICULTY_LEN: usize = 6;
+/// A base64 engine that accepts missing padding and nonzero trailing bits, so that strict decoding can tell them apart from invalid base64.
+const BASE64_LENIENT: GeneralPurpose = GeneralPurpose::new(
+    &alphabet::STANDARD,
+    GeneralPurposeConfig::new()
+        .with_decode_allow_trailing_bits(true)
+        .with_decode_padding_mode(DecodePaddingMode::Indifferent),
+);
 
 /// A proof-of-work system for kCTF.
 ///
 /// All proof-of-work related methods are on instances of [`KctfPow`] in order to initialize and reuse related constants.
+/// Instances are created with [`KctfPow::new`] or one of the other constructors, so that more constants can be added without breaking changes.
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+#[non_exhaustive]
 pub struct KctfPow {
     /// The modulus of the proof-of-work. kCTF uses `2**1279 - 1`.
     pub modulus: Integer,
     /// The exponent of the proof-of-work. kCTF uses `(modulus + 1) / 4`.
+    ///
+    /// This is only used by [`SqrtStrategy::Exponent`].
     pub exponent: Integer,
+    /// How square roots are taken when solving. kCTF uses [`SqrtStrategy::Exponent`].
+    pub sqrt: SqrtStrategy,
+}
+
+/// How a proof-of-work system takes square roots modulo its modulus.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub enum SqrtStrategy {
+    /// Raises values to [`KctfPow::exponent`], which must be `(modulus + 1) / 4`.
+    ///
+    /// This only works when the modulus is 3 mod 4, and is the only strategy that is compatible with kCTF.
+    Exponent,
+    /// Uses the Tonelli-Shanks algorithm, which works for any odd prime modulus.
+    ///
+    /// Values that aren't squares are multiplied by `non_residue` before their root is taken,
+    /// and the parity of the root records whether this happened so that checking can undo it.
+    TonelliShanks {
+        /// A value that isn't a square modulo the modulus.
+        non_residue: Integer,
+    },
 }
 
 /// The parameters for a proof-of-work challenge.
 ///
 /// This contains most of the logic, however [`KctfPow`] and [`Challenge`] should be used instead as they provide a nicer API.
-/// If you want to serialize it to a string, use the [`Display`](std::fmt::Display) implementation.
+/// If you want to serialize it to a string, use the [`Display`](core::fmt::Display) implementation.
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
 pub struct ChallengeParams {
     /// The difficulty of the challenge.
@@ -50,9 +138,66 @@ pub struct ChallengeParams {
     pub val: Integer,
 }
 
+/// The solution to a proof-of-work challenge.
+///
+/// If you want to serialize it to a string, use the [`Display`](core::fmt::Display) implementation.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Solution {
+    /// The value of the solution.
+    pub val: Integer,
+}
+
+/// The state of a partially finished solve, which can be saved and resumed later.
+///
+/// If you want to serialize it to a string, use the [`Display`](core::fmt::Display) implementation, or use [`SolveState::to_bytes`] for a binary format.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct SolveState {
+    /// The challenge being solved.
+    pub params: ChallengeParams,
+    /// The current value of the solve.
+    pub val: Integer,
+    /// The number of iterations done so far.
+    pub done: u32,
+}
+
+/// An error from decoding a challenge, solution, or solve state, from creating a proof-of-work system, or from opening a time-lock puzzle.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[non_exhaustive]
+pub enum PowError {
+    /// The version prefix isn't the one this crate supports. Contains the version that was found.
+    WrongVersion(String),
+    /// The input doesn't have the right number of `.`-separated parts.
+    WrongPartCount,
+    /// A part isn't valid base64.
+    InvalidBase64 {
+        /// The index of the invalid part, where the version is part 0.
+        part: usize,
+    },
+    /// The difficulty doesn't fit in a [`u32`], or the number of iterations of a delay challenge doesn't fit in a [`u64`].
+    DifficultyTooLarge,
+    /// A value is outside of the range it's allowed to be in.
+    ValueOutOfRange,
+    /// The input ended before all of its data was read.
+    Truncated,
+    /// The modulus of a proof-of-work system isn't an odd prime, the modulus of a delay function isn't odd and composite,
+    /// or the modulus of a time-lock puzzle is even or 1.
+    InvalidModulus,
+    /// The input decodes successfully, but isn't in a form that this crate or kCTF encodes it in,
+    /// for example because of missing base64 padding or extra leading zeros. Only returned by strict decoding.
+    NonCanonical,
+    /// The input is longer than any valid input could be. Only returned by strict decoding.
+    TooLong,
+    /// A time-lock puzzle's authentication tag doesn't match, so the puzzle was modified after it was sealed.
+    AuthenticationFailed,
+}
+
+/// The error returned when a solve is stopped early by its progress callback.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Cancelled;
+
 /// A proof-of-work challenge.
 ///
-/// Contains a reference to the [`KctfPow`] that created the challenge. If you want to serialize it to a string, use the [`Display`](std::fmt::Display) implementation.
+/// Contains a reference to the [`KctfPow`] that created the challenge. If you want to serialize it to a string, use the [`Display`](core::fmt::Display) implementation.
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
 pub struct Challenge<'a> {
     /// The parameters of the challenge.
@@ -61,122 +206,512 @@ pub struct Challenge<'a> {
     pub pow: &'a KctfPow,
 }
 
+/// A proof-of-work challenge that uses the [shared](KctfPow::shared) proof-of-work system, and so doesn't borrow anything.
+#[cfg(feature = "std")]
+pub type OwnedChallenge = Challenge<'static>;
+
 impl ChallengeParams {
     /// Decodes a challenge from a string and returns it.
     ///
     /// For optimization purposes, the difficulty of the challenge must be able to fit in a [`u32`].
     /// This shouldn't be an issue, since difficulties that can't fit into a [`u32`] will probably take too long anyways.
-    pub fn decode_challenge(chall_string: &str) -> Result<ChallengeParams, &'static str> {
-        let mut parts = chall_string.split('.');
-        if parts.next() != Some(VERSION) {
-            return Err("Incorrect version");
-        }
-        let data: Vec<_> = parts.collect();
-        if data.len() != 2 {
-            return Err("Incorrect number of parts");
-        }
-        let decoded_data: Vec<_> = data
-            .into_iter()
-            .map(|x| {
-                BASE64_STANDARD
-                    .decode(x)
-                    .map_err(|_| "Parts aren't valid base64")
-            })
-            .collect::<Result<_, _>>()?;
-        let difficulty_bytes = &decoded_data[0];
-        let difficulty: u32 = if difficulty_bytes.len() > 4 {
-            let (first, last) = difficulty_bytes.split_at(difficulty_bytes.len() - 4);
-            // if difficulty is 0-padded to longer than 4 bytes it should still work
-            if first.iter().any(|&x| x != 0) {
-                return Err("Difficulty is too large");
-            }
-            u32::from_be_bytes(last.try_into().unwrap())
-        } else {
-            let mut difficulty_array = [0; 4];
-            difficulty_array[4 - difficulty_bytes.len()..].copy_from_slice(difficulty_bytes);
-            u32::from_be_bytes(difficulty_array)
-        };
+    pub fn decode_challenge(chall_string: &str) -> Result<ChallengeParams, PowError> {
+        let decoded_data = decode_parts(chall_string, VERSION, 2)?;
         Ok(Self {
-            val: Integer::from_digits(&decoded_data[1], Order::Msf),
-            difficulty,
+            val: integer::from_bytes(&decoded_data[1]),
+            difficulty: decode_u32(&decoded_data[0], PowError::DifficultyTooLarge)?,
         })
     }
 
+    /// Decodes a challenge from a string for a given proof-of-work system, rejecting anything that neither this crate nor kCTF would have encoded.
+    ///
+    /// Unlike [`ChallengeParams::decode_challenge`], this returns [`PowError::TooLong`] before decoding anything if the string
+    /// is longer than any valid challenge, [`PowError::NonCanonical`] if a part has missing padding, nonzero trailing bits,
+    /// or more leading zero bytes than kCTF pads numbers with, and [`PowError::ValueOutOfRange`] if the starting value isn't below the modulus.
+    pub fn decode_challenge_strict(
+        chall_string: &str,
+        pow: &KctfPow,
+    ) -> Result<ChallengeParams, PowError> {
+        if chall_string.len()
+            > VERSION.len() + 2 + base64_len(MAX_DIFFICULTY_LEN) + base64_len(pow.max_value_len())
+        {
+            return Err(PowError::TooLong);
+        }
+        let decoded_data = decode_parts_strict(chall_string, VERSION, 2)?;
+        if decoded_data[0].len() > MAX_DIFFICULTY_LEN || decoded_data[1].len() > pow.max_value_len()
+        {
+            return Err(PowError::NonCanonical);
+        }
+        let params = Self {
+            val: integer::from_bytes(&decoded_data[1]),
+            difficulty: decode_u32(&decoded_data[0], PowError::DifficultyTooLarge)?,
+        };
+        if params.val >= pow.modulus {
+            return Err(PowError::ValueOutOfRange);
+        }
+        Ok(params)
+    }
+
     /// Generates a random challenge given a difficulty.
+    #[cfg(feature = "std")]
     pub fn generate_challenge(difficulty: u32) -> ChallengeParams {
-        let mut bytes: [u8; 16] = [0; 16];
-        thread_rng().fill(&mut bytes[..]);
+        Self::generate_challenge_with_rng(&mut thread_rng(), difficulty)
+    }
+
+    /// Generates a random challenge given a difficulty, using a specific random number generator.
+    pub fn generate_challenge_with_rng<R: CryptoRng + RngCore>(
+        rng: &mut R,
+        difficulty: u32,
+    ) -> ChallengeParams {
+        Self::generate_challenge_with_len(rng, difficulty, DEFAULT_VALUE_LEN)
+    }
+
+    /// Generates a random challenge given a difficulty, using a specific random number generator and a starting value that is `value_len` bytes long.
+    ///
+    /// Longer values make it less likely for the same challenge to be generated twice. The other generation methods use 16 bytes.
+    pub fn generate_challenge_with_len<R: CryptoRng + RngCore>(
+        rng: &mut R,
+        difficulty: u32,
+        value_len: usize,
+    ) -> ChallengeParams {
+        let mut bytes = vec![0; value_len];
+        rng.fill_bytes(&mut bytes);
         Self {
-            val: Integer::from_digits(&bytes, Order::Msf),
+            val: integer::from_bytes(&bytes),
             difficulty,
         }
     }
 
+    /// Generates a random challenge given a difficulty along with its solution, without solving it.
+    ///
+    /// See [`ChallengeParams::generate_with_answer_with_rng`] for how the challenge is generated.
+    #[cfg(feature = "std")]
+    pub fn generate_with_answer(pow: &KctfPow, difficulty: u32) -> (ChallengeParams, Solution) {
+        Self::generate_with_answer_with_rng(pow, &mut thread_rng(), difficulty)
+    }
+
+    /// Generates a random challenge given a difficulty along with its solution, using a specific random number generator.
+    ///
+    /// This picks a random solution and undoes `difficulty` iterations of solving on it, which is as fast as checking a solution.
+    /// Submitted solutions can then be checked with [`Solution::check_against_answer`].
+    /// Unlike the other generation methods, the starting value is a random value below the modulus, so the challenge is much longer.
+    pub fn generate_with_answer_with_rng<R: CryptoRng + RngCore>(
+        pow: &KctfPow,
+        rng: &mut R,
+        difficulty: u32,
+    ) -> (ChallengeParams, Solution) {
+        let answer = Solution {
+            val: integer::random_below(rng, &pow.modulus),
+        };
+        let params = Self {
+            val: answer.unsolve(difficulty, pow),
+            difficulty,
+        };
+        (params, answer)
+    }
+
     /// Solves a challenge given a proof-of-work system and returns the solution.
-    pub fn solve(mut self, pow: &KctfPow) -> String {
-        for _ in 0..self.difficulty {
-            // guaranteed to succeed so ignore the result
-            let _ = self.val.pow_mod_mut(&pow.exponent, &pow.modulus);
-            self.val ^= 1;
+    pub fn solve(self, pow: &KctfPow) -> Solution {
+        match self.solve_with(pow, u32::MAX, |_, _| ControlFlow::Continue(())) {
+            Ok(sol) => sol,
+            Err(Cancelled) => unreachable!("solve without a progress callback was cancelled"),
         }
-        format!(
-            "{}.{}",
-            VERSION,
-            BASE64_STANDARD.encode(self.val.to_digits(Order::Msf))
-        )
+    }
+
+    /// Solves a challenge given a proof-of-work system while reporting progress, and returns the solution.
+    ///
+    /// After every `interval` iterations, and once more when the solve finishes, `progress` is called with the number of iterations done
+    /// and the total number of iterations (the difficulty). If it returns [`ControlFlow::Break`] before the solve finishes,
+    /// the solve is stopped and [`Cancelled`] is returned. An `interval` of 0 is treated as 1.
+    pub fn solve_with<F>(
+        self,
+        pow: &KctfPow,
+        interval: u32,
+        progress: F,
+    ) -> Result<Solution, Cancelled>
+    where
+        F: FnMut(u32, u32) -> ControlFlow<()>,
+    {
+        SolveState::new(self).solve_with(pow, interval, progress)
     }
 
     /// Checks a solution to see if it satisfies the challenge under a given proof-of-work system.
-    pub fn check(&self, pow: &KctfPow, sol: &str) -> Result<bool, &'static str> {
-        let mut parts = sol.split('.');
-        if parts.next() != Some(VERSION) {
-            return Err("Incorrect version");
+    pub fn check(&self, pow: &KctfPow, sol: &str) -> Result<bool, PowError> {
+        Ok(sol.parse::<Solution>()?.check(self, pow))
+    }
+
+    /// Checks a solution to see if it satisfies the challenge under a given proof-of-work system,
+    /// decoding it with [`Solution::decode_solution_strict`].
+    ///
+    /// This should be used when checking solutions from untrusted sources, since it bounds the work done on malicious solutions.
+    pub fn check_strict(&self, pow: &KctfPow, sol: &str) -> Result<bool, PowError> {
+        Ok(Solution::decode_solution_strict(sol, pow)?.check(self, pow))
+    }
+}
+
+impl Solution {
+    /// Decodes a solution from a string and returns it.
+    pub fn decode_solution(sol_string: &str) -> Result<Solution, PowError> {
+        let decoded_data = decode_parts(sol_string, VERSION, 1)?;
+        Ok(Self::from_bytes(&decoded_data[0]))
+    }
+
+    /// Decodes a solution from a string for a given proof-of-work system, rejecting anything that neither this crate nor kCTF would have encoded.
+    ///
+    /// Unlike [`Solution::decode_solution`], this returns [`PowError::TooLong`] before decoding anything if the string
+    /// is longer than any valid solution, [`PowError::NonCanonical`] if the value has missing padding, nonzero trailing bits,
+    /// or more leading zero bytes than kCTF pads numbers with, and [`PowError::ValueOutOfRange`] if the value is larger than the modulus.
+    pub fn decode_solution_strict(sol_string: &str, pow: &KctfPow) -> Result<Solution, PowError> {
+        if sol_string.len() > VERSION.len() + 1 + base64_len(pow.max_value_len()) {
+            return Err(PowError::TooLong);
+        }
+        let decoded_data = decode_parts_strict(sol_string, VERSION, 1)?;
+        if decoded_data[0].len() > pow.max_value_len() {
+            return Err(PowError::NonCanonical);
+        }
+        let sol = Self::from_bytes(&decoded_data[0]);
+        // flipping the low bit of `modulus - 1` while solving can give the modulus itself
+        if sol.val > pow.modulus {
+            return Err(PowError::ValueOutOfRange);
+        }
+        Ok(sol)
+    }
+
+    /// Creates a solution from its value as a big-endian integer.
+    pub fn from_bytes(bytes: &[u8]) -> Solution {
+        Self {
+            val: integer::from_bytes(bytes),
+        }
+    }
+
+    /// Returns the value of the solution as a big-endian integer.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        integer::to_bytes(&self.val)
+    }
+
+    /// Checks the solution to see if it satisfies a challenge under a given proof-of-work system.
+    pub fn check(&self, params: &ChallengeParams, pow: &KctfPow) -> bool {
+        Sloth::new(pow).matches(&params.val, &self.unsolve(params.difficulty, pow))
+    }
+
+    /// Checks the solution against the known answer to a challenge, which is much faster than [`Solution::check`].
+    ///
+    /// For difficulties above 0, this accepts the same solutions as [`Solution::check`] would, including unreduced ones.
+    /// When [`SqrtStrategy::Exponent`] is used, the first iteration of checking squares the solution with its low bit flipped,
+    /// so that value is compared to the answer's up to sign.
+    pub fn check_against_answer(&self, answer: &Solution, pow: &KctfPow) -> bool {
+        match pow.sqrt {
+            SqrtStrategy::Exponent => {
+                let squared = |val: &Integer| {
+                    let mut val = val.clone();
+                    integer::flip_low_bit(&mut val);
+                    integer::reduce(&val, &pow.modulus)
+                };
+                let (answer, sol) = (squared(&answer.val), squared(&self.val));
+                answer == sol || integer::negate_mod(&answer, &pow.modulus) == Some(sol)
+            }
+            SqrtStrategy::TonelliShanks { .. } => {
+                integer::reduce(&answer.val, &pow.modulus)
+                    == integer::reduce(&self.val, &pow.modulus)
+            }
+        }
+    }
+
+    /// Undoes `difficulty` iterations of solving, returning the value that the solution is for.
+    fn unsolve(&self, difficulty: u32, pow: &KctfPow) -> Integer {
+        let sloth = Sloth::new(pow);
+        let mut sol_val = self.val.clone();
+        if difficulty > 0 && Mersenne1279::matches(pow) {
+            // the solution may not be reduced, so the first flip has to happen before loading it
+            integer::flip_low_bit(&mut sol_val);
+            let mut val = Mersenne1279::from_integer(&sol_val);
+            val.square();
+            for _ in 1..difficulty {
+                val.flip_low_bit();
+                val.square();
+            }
+            sol_val = val.to_integer();
+        } else {
+            for _ in 0..difficulty {
+                sloth.square_step(&mut sol_val);
+            }
+        }
+        sol_val
+    }
+}
+
+impl SolveState {
+    /// Creates the state of a solve that hasn't started yet.
+    pub fn new(params: ChallengeParams) -> Self {
+        Self {
+            val: params.val.clone(),
+            params,
+            done: 0,
         }
-        let Some(data) = parts.next() else {
-            return Err("Incorrect number of parts");
+    }
+
+    /// Returns whether every iteration of the solve has been done.
+    pub fn is_done(&self) -> bool {
+        self.done >= self.params.difficulty
+    }
+
+    /// Returns the solution if the solve is done.
+    pub fn solution(&self) -> Option<Solution> {
+        if self.is_done() {
+            Some(Solution {
+                val: self.val.clone(),
+            })
+        } else {
+            None
+        }
+    }
+
+    /// Continues the solve given a proof-of-work system while reporting progress, and returns the solution.
+    ///
+    /// This works like [`ChallengeParams::solve_with`], except that `progress` is only called once the number of iterations done
+    /// has moved past where the solve was resumed from. If the solve is cancelled, the state is left at the last iteration reached
+    /// so that it can be saved and resumed later.
+    pub fn solve_with<F>(
+        &mut self,
+        pow: &KctfPow,
+        interval: u32,
+        mut progress: F,
+    ) -> Result<Solution, Cancelled>
+    where
+        F: FnMut(u32, u32) -> ControlFlow<()>,
+    {
+        let interval = interval.max(1);
+        let total = self.params.difficulty;
+        let start = self.done;
+        let sloth = Sloth::new(pow);
+        let mut fast_val = if !self.is_done() && Mersenne1279::matches(pow) {
+            Some(Mersenne1279::from_integer(&self.val))
+        } else {
+            None
         };
-        if parts.next().is_some() {
-            return Err("Incorrect number of parts");
+        while self.done < total {
+            if self.done != start
+                && self.done.is_multiple_of(interval)
+                && progress(self.done, total).is_break()
+            {
+                if let Some(val) = fast_val {
+                    self.val = val.to_integer();
+                }
+                return Err(Cancelled);
+            }
+            match &mut fast_val {
+                Some(val) => {
+                    val.sqrt();
+                    val.flip_low_bit();
+                }
+                None => sloth.root_step(&mut self.val),
+            }
+            self.done += 1;
         }
-        let decoded_data = BASE64_STANDARD
-            .decode(data)
-            .map_err(|_| "Parts aren't valid base64")?;
-        let mut sol_val = Integer::from_digits(&decoded_data, Order::Msf);
-        for _ in 0..self.difficulty {
-            sol_val ^= 1;
-            // guaranteed to succeed so ignore the result
-            let _ = sol_val.pow_mod_mut(&2.into(), &pow.modulus);
+        if let Some(val) = fast_val {
+            self.val = val.to_integer();
         }
-        Ok(self.val == sol_val || Integer::from(&pow.modulus - &self.val) == sol_val)
+        // the solve is already done so there's nothing left to cancel
+        let _ = progress(total, total);
+        Ok(Solution {
+            val: self.val.clone(),
+        })
+    }
+
+    /// Decodes the state of a solve from a string and returns it.
+    ///
+    /// The string is the challenge followed by the number of iterations done and the current value, in the same format as the challenge.
+    pub fn decode_state(state_string: &str) -> Result<SolveState, PowError> {
+        let decoded_data = decode_parts(state_string, VERSION, 4)?;
+        Self::from_parts(
+            &decoded_data[0],
+            &decoded_data[1],
+            &decoded_data[2],
+            &decoded_data[3],
+        )
+    }
+
+    /// Encodes the state of a solve into bytes.
+    ///
+    /// The bytes are the difficulty, the number of iterations done, and the length of the starting value as big-endian [`u32`]s,
+    /// followed by the starting value and the current value as big-endian integers.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let start_val = integer::to_bytes(&self.params.val);
+        let val = integer::to_bytes(&self.val);
+        let mut bytes = Vec::with_capacity(12 + start_val.len() + val.len());
+        bytes.extend_from_slice(&self.params.difficulty.to_be_bytes());
+        bytes.extend_from_slice(&self.done.to_be_bytes());
+        bytes.extend_from_slice(&(start_val.len() as u32).to_be_bytes());
+        bytes.extend_from_slice(&start_val);
+        bytes.extend_from_slice(&val);
+        bytes
+    }
+
+    /// Decodes the state of a solve from bytes produced by [`SolveState::to_bytes`] and returns it.
+    pub fn from_bytes(bytes: &[u8]) -> Result<SolveState, PowError> {
+        if bytes.len() < 12 {
+            return Err(PowError::Truncated);
+        }
+        let (header, rest) = bytes.split_at(12);
+        let start_len = u32::from_be_bytes(header[8..].try_into().unwrap()) as usize;
+        if rest.len() < start_len {
+            return Err(PowError::Truncated);
+        }
+        let (start_val, val) = rest.split_at(start_len);
+        Self::from_parts(&header[..4], start_val, &header[4..8], val)
+    }
+
+    fn from_parts(
+        difficulty: &[u8],
+        start_val: &[u8],
+        done: &[u8],
+        val: &[u8],
+    ) -> Result<SolveState, PowError> {
+        let params = ChallengeParams {
+            difficulty: decode_u32(difficulty, PowError::DifficultyTooLarge)?,
+            val: integer::from_bytes(start_val),
+        };
+        let done = decode_u32(done, PowError::ValueOutOfRange)?;
+        if done > params.difficulty {
+            return Err(PowError::ValueOutOfRange);
+        }
+        Ok(Self {
+            params,
+            val: integer::from_bytes(val),
+            done,
+        })
     }
 }
 
 impl KctfPow {
     /// Create a new instance and initialize necessary constants.
     pub fn new() -> Self {
-        let modulus: Integer = Integer::from(2).pow(1279) - 1;
-        let exponent = (modulus.clone() + 1) / 4;
-        Self { modulus, exponent }
+        Self::mersenne(1279)
+    }
+
+    /// Create a new instance with the Mersenne prime `2**521 - 1` as the modulus.
+    ///
+    /// This is much faster to solve than kCTF's modulus, so it's useful for testing, but isn't compatible with kCTF.
+    pub fn mersenne_521() -> Self {
+        Self::mersenne(521)
+    }
+
+    /// Create a new instance with the Mersenne prime `2**607 - 1` as the modulus.
+    ///
+    /// This is much faster to solve than kCTF's modulus, so it's useful for testing, but isn't compatible with kCTF.
+    pub fn mersenne_607() -> Self {
+        Self::mersenne(607)
+    }
+
+    /// Create a new instance with a custom prime modulus, picking the fastest way to take square roots for it.
+    ///
+    /// If the modulus is 3 mod 4, [`SqrtStrategy::Exponent`] is used, otherwise [`SqrtStrategy::TonelliShanks`] is used.
+    /// Returns [`PowError::InvalidModulus`] if the modulus isn't an odd prime. Primality is checked with Miller-Rabin,
+    /// so a composite modulus that was specially constructed to fool it may be accepted.
+    pub fn with_modulus(modulus: Integer) -> Result<Self, PowError> {
+        if !integer::is_odd(&modulus) || !prime::is_probable_prime(&modulus) {
+            return Err(PowError::InvalidModulus);
+        }
+        let exponent = (modulus.clone() + 1u32) / 4u32;
+        let sqrt = if integer::reduce(&modulus, &Integer::from(4u32)) == Integer::from(3u32) {
+            SqrtStrategy::Exponent
+        } else {
+            SqrtStrategy::TonelliShanks {
+                non_residue: sqrt::find_non_residue(&modulus),
+            }
+        };
+        Ok(Self {
+            modulus,
+            exponent,
+            sqrt,
+        })
+    }
+
+    /// Create a new instance with the Mersenne prime `2**exp - 1` as the modulus, which must be prime.
+    fn mersenne(exp: u32) -> Self {
+        let modulus = integer::mersenne(exp);
+        let exponent = (modulus.clone() + 1u32) / 4u32;
+        Self {
+            modulus,
+            exponent,
+            sqrt: SqrtStrategy::Exponent,
+        }
     }
 
     /// Decodes a challenge from a string and returns it.
     ///
     /// For optimization purposes, the difficulty of the challenge must be able to fit in a [`u32`].
     /// This shouldn't be an issue, since difficulties that can't fit into a [`u32`] will probably take too long anyways.
-    pub fn decode_challenge(&self, chall_string: &str) -> Result<Challenge, &'static str> {
+    pub fn decode_challenge(&self, chall_string: &str) -> Result<Challenge<'_>, PowError> {
         Ok(Challenge {
             params: ChallengeParams::decode_challenge(chall_string)?,
             pow: self,
         })
     }
 
+    /// Decodes a challenge from a string, rejecting anything that this crate wouldn't have encoded.
+    ///
+    /// See [`ChallengeParams::decode_challenge_strict`] for what is rejected.
+    pub fn decode_challenge_strict(&self, chall_string: &str) -> Result<Challenge<'_>, PowError> {
+        Ok(Challenge {
+            params: ChallengeParams::decode_challenge_strict(chall_string, self)?,
+            pow: self,
+        })
+    }
+
     /// Generates a random challenge given a difficulty.
-    pub fn generate_challenge(&self, difficulty: u32) -> Challenge {
+    #[cfg(feature = "std")]
+    pub fn generate_challenge(&self, difficulty: u32) -> Challenge<'_> {
         Challenge {
             params: ChallengeParams::generate_challenge(difficulty),
             pow: self,
         }
     }
+
+    /// Generates a random challenge given a difficulty, using a specific random number generator.
+    pub fn generate_challenge_with_rng<R: CryptoRng + RngCore>(
+        &self,
+        rng: &mut R,
+        difficulty: u32,
+    ) -> Challenge<'_> {
+        Challenge {
+            params: ChallengeParams::generate_challenge_with_rng(rng, difficulty),
+            pow: self,
+        }
+    }
+
+    /// Generates a random challenge given a difficulty along with its solution, without solving it.
+    ///
+    /// See [`ChallengeParams::generate_with_answer_with_rng`] for how the challenge is generated.
+    #[cfg(feature = "std")]
+    pub fn generate_with_answer(&self, difficulty: u32) -> (Challenge<'_>, Solution) {
+        self.generate_with_answer_with_rng(&mut thread_rng(), difficulty)
+    }
+
+    /// Generates a random challenge given a difficulty along with its solution, using a specific random number generator.
+    pub fn generate_with_answer_with_rng<R: CryptoRng + RngCore>(
+        &self,
+        rng: &mut R,
+        difficulty: u32,
+    ) -> (Challenge<'_>, Solution) {
+        let (params, answer) =
+            ChallengeParams::generate_with_answer_with_rng(self, rng, difficulty);
+        (Challenge { params, pow: self }, answer)
+    }
+
+    /// Returns a shared instance, which is created the first time this is called.
+    ///
+    /// Challenges created from the shared instance don't borrow anything, so they can be stored in long-lived structures or sent to other threads.
+    #[cfg(feature = "std")]
+    pub fn shared() -> &'static KctfPow {
+        static SHARED: OnceLock<KctfPow> = OnceLock::new();
+        SHARED.get_or_init(KctfPow::new)
+    }
+
+    /// Returns the number of bytes in the longest value that strict decoding accepts, which is how long kCTF encodes the modulus.
+    fn max_value_len(&self) -> usize {
+        kctf_len(&integer::to_bytes(&self.modulus))
+    }
 }
 
 impl Default for KctfPow {
@@ -185,16 +720,70 @@ impl Default for KctfPow {
     }
 }
 
+#[cfg(feature = "std")]
+impl Challenge<'static> {
+    /// Decodes a challenge from a string using the [shared](KctfPow::shared) proof-of-work system and returns it.
+    pub fn decode_owned(chall_string: &str) -> Result<OwnedChallenge, PowError> {
+        KctfPow::shared().decode_challenge(chall_string)
+    }
+
+    /// Generates a random challenge given a difficulty using the [shared](KctfPow::shared) proof-of-work system.
+    pub fn generate_owned(difficulty: u32) -> OwnedChallenge {
+        KctfPow::shared().generate_challenge(difficulty)
+    }
+}
+
+#[cfg(feature = "std")]
+impl From<ChallengeParams> for OwnedChallenge {
+    fn from(params: ChallengeParams) -> Self {
+        Challenge {
+            params,
+            pow: KctfPow::shared(),
+        }
+    }
+}
+
 impl<'a> Challenge<'a> {
     /// Solves a challenge and returns the solution.
-    pub fn solve(self) -> String {
+    pub fn solve(self) -> Solution {
         self.params.solve(self.pow)
     }
 
+    /// Solves a challenge while reporting progress and returns the solution.
+    ///
+    /// See [`ChallengeParams::solve_with`] for how `interval` and `progress` are used.
+    pub fn solve_with<F>(self, interval: u32, progress: F) -> Result<Solution, Cancelled>
+    where
+        F: FnMut(u32, u32) -> ControlFlow<()>,
+    {
+        self.params.solve_with(self.pow, interval, progress)
+    }
+
     /// Checks a solution to see if it satisfies the challenge.
-    pub fn check(&self, sol: &str) -> Result<bool, &'static str> {
+    pub fn check(&self, sol: &str) -> Result<bool, PowError> {
         self.params.check(self.pow, sol)
     }
+
+    /// Checks a solution to see if it satisfies the challenge, decoding it with [`Solution::decode_solution_strict`].
+    ///
+    /// This should be used when checking solutions from untrusted sources, since it bounds the work done on malicious solutions.
+    pub fn check_strict(&self, sol: &str) -> Result<bool, PowError> {
+        self.params.check_strict(self.pow, sol)
+    }
+
+    /// Checks a solution against the known answer to the challenge, which is much faster than [`Challenge::check`].
+    ///
+    /// See [`Solution::check_against_answer`] for which solutions are accepted.
+    pub fn check_against_answer(&self, sol: &str, answer: &Solution) -> Result<bool, PowError> {
+        Ok(sol
+            .parse::<Solution>()?
+            .check_against_answer(answer, self.pow))
+    }
+
+    /// Checks an already decoded solution to see if it satisfies the challenge.
+    pub fn check_solution(&self, sol: &Solution) -> bool {
+        sol.check(&self.params, self.pow)
+    }
 }
 
 impl fmt::Display for ChallengeParams {
@@ -204,7 +793,30 @@ impl fmt::Display for ChallengeParams {
             "{}.{}.{}",
             VERSION,
             BASE64_STANDARD.encode(self.difficulty.to_be_bytes()),
-            BASE64_STANDARD.encode(self.val.to_digits(Order::Msf))
+            BASE64_STANDARD.encode(integer::to_bytes(&self.val))
+        )
+    }
+}
+
+impl fmt::Display for Solution {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            fmt,
+            "{}.{}",
+            VERSION,
+            BASE64_STANDARD.encode(integer::to_bytes(&self.val))
+        )
+    }
+}
+
+impl fmt::Display for SolveState {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            fmt,
+            "{}.{}.{}",
+            self.params,
+            BASE64_STANDARD.encode(self.done.to_be_bytes()),
+            BASE64_STANDARD.encode(integer::to_bytes(&self.val))
         )
     }
 }
@@ -214,3 +826,160 @@ impl<'a> fmt::Display for Challenge<'a> {
         write!(fmt, "{}", self.params)
     }
 }
+
+impl FromStr for ChallengeParams {
+    type Err = PowError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::decode_challenge(s)
+    }
+}
+
+impl FromStr for Solution {
+    type Err = PowError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::decode_solution(s)
+    }
+}
+
+impl FromStr for SolveState {
+    type Err = PowError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::decode_state(s)
+    }
+}
+
+impl PowError {
+    /// Returns a number that identifies the kind of error, which is the same as its status in the C API.
+    ///
+    /// The CLI exits with this as its status code. Codes start at 2 and are never reused for a different kind of error.
+    pub fn code(&self) -> u8 {
+        match self {
+            PowError::WrongVersion(_) => 2,
+            PowError::WrongPartCount => 3,
+            PowError::InvalidBase64 { .. } => 4,
+            PowError::DifficultyTooLarge => 5,
+            PowError::ValueOutOfRange => 6,
+            PowError::Truncated => 7,
+            PowError::NonCanonical => 8,
+            PowError::TooLong => 9,
+            PowError::AuthenticationFailed => 10,
+            PowError::InvalidModulus => 11,
+        }
+    }
+}
+
+impl fmt::Display for PowError {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PowError::WrongVersion(found) => write!(fmt, "Incorrect version {:?}", found),
+            PowError::WrongPartCount => write!(fmt, "Incorrect number of parts"),
+            PowError::InvalidBase64 { part } => write!(fmt, "Part {} isn't valid base64", part),
+            PowError::DifficultyTooLarge => write!(fmt, "Difficulty is too large"),
+            PowError::ValueOutOfRange => write!(fmt, "Value is out of range"),
+            PowError::Truncated => write!(fmt, "Input is truncated"),
+            PowError::InvalidModulus => write!(fmt, "Modulus is not valid"),
+            PowError::NonCanonical => write!(fmt, "Input is not canonically encoded"),
+            PowError::TooLong => write!(fmt, "Input is too long"),
+            PowError::AuthenticationFailed => write!(fmt, "Authentication failed"),
+        }
+    }
+}
+
+impl Error for PowError {}
+
+impl fmt::Display for Cancelled {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(fmt, "Solve was cancelled")
+    }
+}
+
+impl Error for Cancelled {}
+
+/// Splits a string into its version and `count` base64 parts, checks that the version is `version`, and decodes the parts.
+fn decode_parts(string: &str, version: &str, count: usize) -> Result<Vec<Vec<u8>>, PowError> {
+    decode_parts_with(&BASE64_STANDARD, string, version, count)
+}
+
+/// Like [`decode_parts`], but returns [`PowError::NonCanonical`] for parts that are only invalid because of their padding or trailing bits.
+fn decode_parts_strict(
+    string: &str,
+    version: &str,
+    count: usize,
+) -> Result<Vec<Vec<u8>>, PowError> {
+    let decoded_data = decode_parts_with(&BASE64_LENIENT, string, version, count)?;
+    for (part, bytes) in string.split('.').skip(1).zip(&decoded_data) {
+        if BASE64_STANDARD.encode(bytes) != part {
+            return Err(PowError::NonCanonical);
+        }
+    }
+    Ok(decoded_data)
+}
+
+/// Like [`decode_parts`], but decodes the parts with `engine`.
+fn decode_parts_with<E: Engine>(
+    engine: &E,
+    string: &str,
+    version: &str,
+    count: usize,
+) -> Result<Vec<Vec<u8>>, PowError> {
+    let mut parts = string.split('.');
+    match parts.next() {
+        Some(found) if found == version => {}
+        found => return Err(PowError::WrongVersion(found.unwrap_or_default().into())),
+    }
+    let data: Vec<_> = parts.collect();
+    if data.len() != count {
+        return Err(PowError::WrongPartCount);
+    }
+    data.into_iter()
+        .enumerate()
+        .map(|(i, x)| {
+            engine
+                .decode(x)
+                .map_err(|_| PowError::InvalidBase64 { part: i + 1 })
+        })
+        .collect()
+}
+
+/// Returns the length of the padded base64 encoding of `len` bytes.
+fn base64_len(len: usize) -> usize {
+    len.div_ceil(3) * 4
+}
+
+/// Returns the number of bytes that kCTF's `encode_number` pads a big-endian number to, which is always a multiple of 3.
+fn kctf_len(bytes: &[u8]) -> usize {
+    let bits = match bytes.first() {
+        Some(first) => bytes.len() * 8 - first.leading_zeros() as usize,
+        None => 0,
+    };
+    bits / 24 * 3 + 3
+}
+
+/// Decodes a big-endian [`u32`], returning `too_large` if it doesn't fit.
+fn decode_u32(bytes: &[u8], too_large: PowError) -> Result<u32, PowError> {
+    decode_be(bytes, too_large).map(u32::from_be_bytes)
+}
+
+/// Decodes a big-endian [`u64`], returning `too_large` if it doesn't fit.
+fn decode_u64(bytes: &[u8], too_large: PowError) -> Result<u64, PowError> {
+    decode_be(bytes, too_large).map(u64::from_be_bytes)
+}
+
+/// Decodes a big-endian number into exactly `N` bytes, returning `too_large` if it doesn't fit.
+fn decode_be<const N: usize>(bytes: &[u8], too_large: PowError) -> Result<[u8; N], PowError> {
+    if bytes.len() > N {
+        let (first, last) = bytes.split_at(bytes.len() - N);
+        // if the number is 0-padded to longer than N bytes it should still work
+        if first.iter().any(|&x| x != 0) {
+            return Err(too_large);
+        }
+        Ok(last.try_into().unwrap())
+    } else {
+        let mut array = [0; N];
+        array[N - bytes.len()..].copy_from_slice(bytes);
+        Ok(array)
+    }
+}
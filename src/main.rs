@@ -1,68 +1,533 @@
-use kctf_pow::KctfPow;
+use kctf_pow::{
+    scheme_version, ChallengeParams, Hashcash, HashcashParams, KctfPow, PowError, PowScheme, Solution, SolveState, TimeLock,
+};
+use rand::SeedableRng;
+use rand_chacha::ChaCha20Rng;
+use sha2::{Digest, Sha256};
+use std::fmt;
+use std::io::{ErrorKind, Read, Write};
+use std::ops::ControlFlow;
+#[cfg(feature = "rpc")]
+use serde_json::{json, Value};
+#[cfg(feature = "rpc")]
+use std::convert::TryInto;
+#[cfg(feature = "rpc")]
+use std::sync::{mpsc, Mutex};
+#[cfg(feature = "rpc")]
+use std::thread;
+
+/// How many iterations to do between saving checkpoints.
+const CHECKPOINT_INTERVAL: u32 = 1000;
+
+/// An error that ends the program.
+///
+/// Each kind of [`PowError`] exits with its own [code](PowError::code), and every other error exits with 1.
+enum CliError {
+    Pow(PowError),
+    Other(String),
+}
+
+impl CliError {
+    fn exit_code(&self) -> i32 {
+        match self {
+            CliError::Other(_) => 1,
+            CliError::Pow(err) => err.code().into(),
+        }
+    }
+}
+
+impl From<PowError> for CliError {
+    fn from(err: PowError) -> Self {
+        CliError::Pow(err)
+    }
+}
+
+impl From<String> for CliError {
+    fn from(s: String) -> Self {
+        CliError::Other(s)
+    }
+}
+
+impl From<&str> for CliError {
+    fn from(s: &str) -> Self {
+        CliError::Other(s.into())
+    }
+}
+
+impl fmt::Display for CliError {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CliError::Pow(err) => write!(fmt, "{}", err),
+            CliError::Other(s) => write!(fmt, "{}", s),
+        }
+    }
+}
 
 fn gen_usage(name: &str) -> String {
     format!(
         "Could not parse arguments
 Usage:
-    To solve a challenge: {0} solve <challenge>
-    To check a challenge: {0} check <challenge>
-    To randomly generate a challenge: {0} gen <difficulty>
-    To chain generation with checking: {0} ask <difficulty>\
+    To solve challenges: {0} solve [--threads <count>] [<challenge>...]
+    To solve a challenge with checkpoints: {0} solve --checkpoint <file> <challenge>
+    To check a challenge: {0} check [--strict] <challenge>
+    To randomly generate a challenge: {0} gen [--seed <string>] [--scheme <version>] <difficulty>
+    To chain generation with checking: {0} ask [--scheme <version>] <difficulty>
+    To seal stdin into a time-lock puzzle: {0} timelock seal <iterations>
+    To open a time-lock puzzle: {0} timelock open <puzzle>
+    To answer JSON-RPC requests from stdin: {0} rpc\
 ",
         name
     )
 }
 
-fn actual_main() -> Result<(), String> {
+/// Derives the seed of a random number generator by hashing a string, so that every string gives a different seed.
+fn derive_seed(seed: &str) -> [u8; 32] {
+    Sha256::digest(seed.as_bytes()).into()
+}
+
+fn save_checkpoint(state: &SolveState, file: &str) -> Result<(), CliError> {
+    // write to a temporary file first so that the checkpoint isn't corrupted if we get killed while writing
+    let tmp_file = format!("{}.tmp", file);
+    std::fs::write(&tmp_file, format!("{}\n", state)).map_err(|_| "Could not write checkpoint")?;
+    std::fs::rename(&tmp_file, file).map_err(|_| "Could not write checkpoint")?;
+    Ok(())
+}
+
+fn solve_with_checkpoint(pow: &KctfPow, params: ChallengeParams, file: &str) -> Result<Solution, CliError> {
+    let mut state = match std::fs::read_to_string(file) {
+        Ok(contents) => {
+            let state = SolveState::decode_state(contents.trim())?;
+            if state.params != params {
+                return Err("Checkpoint is for a different challenge".into());
+            }
+            state
+        }
+        Err(e) if e.kind() == ErrorKind::NotFound => SolveState::new(params),
+        Err(_) => return Err("Could not read checkpoint".into()),
+    };
+    loop {
+        // stop after every interval to save the checkpoint, then resume
+        let res = state.solve_with(pow, CHECKPOINT_INTERVAL, |_, _| ControlFlow::Break(()));
+        save_checkpoint(&state, file)?;
+        if let Ok(sol) = res {
+            return Ok(sol);
+        }
+    }
+}
+
+/// Parses the arguments of `solve` into the checkpoint file, the thread count, and the challenges.
+fn parse_solve_args(mut args: &[String]) -> Option<(Option<&str>, Option<&str>, &[String])> {
+    let mut checkpoint = None;
+    let mut threads = None;
+    loop {
+        match args {
+            [flag, file, rest @ ..] if flag == "--checkpoint" => {
+                checkpoint = Some(file as _);
+                args = rest;
+            }
+            [flag, count, rest @ ..] if flag == "--threads" => {
+                threads = Some(count as _);
+                args = rest;
+            }
+            [flag, ..] if flag.starts_with("--") => return None,
+            _ => return Some((checkpoint, threads, args)),
+        }
+    }
+}
+
+/// A decoded challenge of any scheme.
+enum AnyChallenge {
+    Sloth(ChallengeParams),
+    Hashcash(HashcashParams),
+}
+
+/// Solves challenges of any scheme and returns their solutions in order, solving the sloth challenges on `threads` worker threads
+/// and each hashcash challenge on `threads` threads of its own.
+fn solve_all(pow: &KctfPow, chall_strings: &[String], threads: usize) -> Result<Vec<String>, CliError> {
+    // decode everything first so that a malformed challenge is reported before any solving starts
+    let challs = chall_strings
+        .iter()
+        .map(|chall_string| {
+            Ok(if scheme_version(chall_string) == Hashcash::VERSION {
+                AnyChallenge::Hashcash(Hashcash.decode_challenge(chall_string)?)
+            } else {
+                AnyChallenge::Sloth(ChallengeParams::decode_challenge(chall_string)?)
+            })
+        })
+        .collect::<Result<Vec<_>, CliError>>()?;
+    let sloth_challs: Vec<_> = challs
+        .iter()
+        .filter_map(|chall| match chall {
+            AnyChallenge::Sloth(params) => Some(params.clone()),
+            AnyChallenge::Hashcash(_) => None,
+        })
+        .collect();
+    let mut sloth_sols = pow.solve_many(&sloth_challs, threads).into_iter();
+    Ok(challs
+        .iter()
+        .map(|chall| match chall {
+            AnyChallenge::Sloth(_) => sloth_sols.next().expect("every sloth challenge should have a solution").to_string(),
+            AnyChallenge::Hashcash(params) => Hashcash.encode_solution(&params.solve_parallel(threads)),
+        })
+        .collect())
+}
+
+/// Parses the arguments of `gen` and `ask` into the seed, the version of the scheme, and the difficulty.
+fn parse_gen_args(mut args: &[String]) -> Option<(Option<&str>, &str, &str)> {
+    let mut seed = None;
+    let mut version = KctfPow::VERSION;
+    loop {
+        match args {
+            [flag, val, rest @ ..] if flag == "--seed" => {
+                seed = Some(val as _);
+                args = rest;
+            }
+            [flag, val, rest @ ..] if flag == "--scheme" => {
+                version = val;
+                args = rest;
+            }
+            [difficulty] => return Some((seed, version, difficulty)),
+            _ => return None,
+        }
+    }
+}
+
+/// Generates a challenge, deterministically if a seed is given.
+fn generate<S: PowScheme>(scheme: &S, seed: Option<[u8; 32]>, difficulty: u32) -> Result<S::Challenge, PowError> {
+    match seed {
+        Some(seed) => scheme.generate_with_rng(&mut ChaCha20Rng::from_seed(seed), difficulty),
+        None => scheme.generate(difficulty),
+    }
+}
+
+/// Generates a challenge and prints it, then if `ask` is set, reads a solution from stdin and checks it.
+fn gen_and_ask<S: PowScheme>(scheme: &S, seed: Option<[u8; 32]>, difficulty: u32, ask: bool) -> Result<(), CliError> {
+    let chall = generate(scheme, seed, difficulty)?;
+    println!("{}", scheme.encode_challenge(&chall));
+    if ask {
+        let sol = scheme.decode_solution(read_line()?.trim())?;
+        report_check(scheme.check(&chall, &sol))?;
+    }
+    Ok(())
+}
+
+/// Checks a solution for a challenge of any scheme.
+///
+/// The solution is only fetched once the challenge is decoded, so that an invalid challenge is reported without waiting for a solution.
+fn check<F>(pow: &KctfPow, chall_string: &str, strict: bool, sol: F) -> Result<bool, CliError>
+where
+    F: FnOnce() -> Result<String, CliError>,
+{
+    // hashcash solutions are always cheap to check, so strict checking only changes how sloth challenges are checked
+    Ok(if scheme_version(chall_string) == Hashcash::VERSION {
+        let chall = Hashcash.decode_challenge(chall_string)?;
+        Hashcash.check(&chall, &Hashcash.decode_solution(sol()?.trim())?)
+    } else if strict {
+        pow.decode_challenge_strict(chall_string)?.check_strict(sol()?.trim())?
+    } else {
+        pow.decode_challenge(chall_string)?.check(sol()?.trim())?
+    })
+}
+
+fn read_line() -> Result<String, CliError> {
+    let mut inp = String::new();
+    std::io::stdin().read_line(&mut inp).map_err(|_| "Could not read from stdin")?;
+    Ok(inp)
+}
+
+fn report_check(res: bool) -> Result<(), CliError> {
+    if res {
+        println!("correct");
+        Ok(())
+    } else {
+        Err("incorrect".into())
+    }
+}
+
+/// An error response to a JSON-RPC request.
+#[cfg(feature = "rpc")]
+struct RpcError {
+    code: i32,
+    message: String,
+}
+
+#[cfg(feature = "rpc")]
+impl RpcError {
+    fn parse_error() -> Self {
+        Self { code: -32700, message: "Parse error".into() }
+    }
+
+    fn invalid_request() -> Self {
+        Self { code: -32600, message: "Invalid request".into() }
+    }
+
+    fn method_not_found(method: &str) -> Self {
+        Self { code: -32601, message: format!("Unknown method {:?}", method) }
+    }
+
+    fn invalid_params(message: String) -> Self {
+        Self { code: -32602, message }
+    }
+}
+
+/// Errors from decoding or checking have the same codes as the exit codes of the CLI.
+#[cfg(feature = "rpc")]
+impl From<CliError> for RpcError {
+    fn from(err: CliError) -> Self {
+        Self { code: err.exit_code(), message: err.to_string() }
+    }
+}
+
+#[cfg(feature = "rpc")]
+impl From<PowError> for RpcError {
+    fn from(err: PowError) -> Self {
+        CliError::Pow(err).into()
+    }
+}
+
+/// Gets a parameter of a JSON-RPC request, which is `null` if it's missing.
+#[cfg(feature = "rpc")]
+fn rpc_param<'a>(params: &'a Value, name: &str) -> &'a Value {
+    params.get(name).unwrap_or(&Value::Null)
+}
+
+#[cfg(feature = "rpc")]
+fn rpc_str<'a>(params: &'a Value, name: &str) -> Result<&'a str, RpcError> {
+    rpc_param(params, name).as_str().ok_or_else(|| RpcError::invalid_params(format!("{:?} must be a string", name)))
+}
+
+#[cfg(feature = "rpc")]
+fn rpc_u32(params: &Value, name: &str) -> Result<u32, RpcError> {
+    rpc_param(params, name)
+        .as_u64()
+        .and_then(|x| x.try_into().ok())
+        .ok_or_else(|| RpcError::invalid_params(format!("{:?} must be a 32-bit unsigned integer", name)))
+}
+
+/// Answers a JSON-RPC request that isn't a solve.
+#[cfg(feature = "rpc")]
+fn rpc_call(pow: &KctfPow, method: &str, params: &Value) -> Result<Value, RpcError> {
+    match method {
+        "generate" => {
+            let difficulty = rpc_u32(params, "difficulty")?;
+            let version = match rpc_param(params, "scheme") {
+                Value::Null => KctfPow::VERSION,
+                _ => rpc_str(params, "scheme")?,
+            };
+            let seed = match rpc_param(params, "seed") {
+                Value::Null => None,
+                _ => Some(derive_seed(rpc_str(params, "seed")?)),
+            };
+            let chall = match version {
+                KctfPow::VERSION => pow.encode_challenge(&generate(pow, seed, difficulty)?),
+                Hashcash::VERSION => Hashcash.encode_challenge(
+                    &generate(&Hashcash, seed, difficulty).map_err(|err| RpcError::invalid_params(err.to_string()))?,
+                ),
+                _ => return Err(RpcError::invalid_params(format!("Unknown scheme {:?}", version))),
+            };
+            Ok(chall.into())
+        }
+        "check" => {
+            let chall_string = rpc_str(params, "challenge")?;
+            let sol = rpc_str(params, "solution")?;
+            let strict = match rpc_param(params, "strict") {
+                Value::Null => false,
+                strict => strict.as_bool().ok_or_else(|| RpcError::invalid_params("\"strict\" must be a boolean".into()))?,
+            };
+            Ok(check(pow, chall_string, strict, || Ok(sol.into()))?.into())
+        }
+        "inspect" => {
+            let chall_string = rpc_str(params, "challenge")?;
+            let (version, difficulty) = match scheme_version(chall_string) {
+                Hashcash::VERSION => (Hashcash::VERSION, Hashcash.decode_challenge(chall_string)?.difficulty),
+                _ => (KctfPow::VERSION, ChallengeParams::decode_challenge(chall_string)?.difficulty),
+            };
+            Ok(json!({ "scheme": version, "difficulty": difficulty }))
+        }
+        method => Err(RpcError::method_not_found(method)),
+    }
+}
+
+/// Writes the response to a JSON-RPC request as a single line.
+#[cfg(feature = "rpc")]
+fn rpc_respond(id: &Value, res: Result<Value, RpcError>) {
+    let response = match res {
+        Ok(result) => json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+        Err(err) => json!({ "jsonrpc": "2.0", "id": id, "error": { "code": err.code, "message": err.message } }),
+    };
+    // a single call holds the lock on stdout, so responses from different threads don't interleave
+    println!("{}", response);
+}
+
+/// Reads JSON-RPC requests from stdin, one per line, and writes a response to each one on stdout.
+///
+/// Solves are queued for a fixed set of worker threads, one per CPU, which answer them while other requests are answered here,
+/// so responses may be out of order. Once stdin is closed, this waits for the queued solves to finish.
+#[cfg(feature = "rpc")]
+fn rpc(pow: &'static KctfPow) -> Result<(), CliError> {
+    let (queue, jobs) = mpsc::channel::<(Option<Value>, Value)>();
+    let jobs = Mutex::new(jobs);
+    let threads = thread::available_parallelism().map_or(1, |x| x.get());
+    thread::scope(|scope| {
+        for _ in 0..threads {
+            scope.spawn(|| loop {
+                // the lock is only held while waiting, so that other workers can take the next solve
+                let job = jobs.lock().unwrap_or_else(|err| err.into_inner()).recv();
+                let (id, params) = match job {
+                    Ok(job) => job,
+                    Err(_) => return,
+                };
+                let solve = || {
+                    rpc_str(&params, "challenge")
+                        .and_then(|chall_string| Ok(solve_all(pow, &[chall_string.into()], 1)?.remove(0).into()))
+                };
+                // a panicking solve has already printed its message, and the worker should still take other solves
+                if let (Ok(res), Some(id)) = (std::panic::catch_unwind(solve), id) {
+                    rpc_respond(&id, res);
+                }
+            });
+        }
+        let res = rpc_read(pow, &queue);
+        // closing the queue lets the workers exit once they've finished the solves in it
+        drop(queue);
+        res
+    })
+}
+
+/// Answers the JSON-RPC requests from stdin that aren't solves, and sends the solves to `queue`.
+#[cfg(feature = "rpc")]
+fn rpc_read(pow: &KctfPow, queue: &mpsc::Sender<(Option<Value>, Value)>) -> Result<(), CliError> {
+    for line in std::io::stdin().lines() {
+        let line = line.map_err(|_| "Could not read from stdin")?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let request: Value = match serde_json::from_str(&line) {
+            Ok(request) => request,
+            Err(_) => {
+                rpc_respond(&Value::Null, Err(RpcError::parse_error()));
+                continue;
+            }
+        };
+        let params = rpc_param(&request, "params").clone();
+        // requests without an id are notifications, which don't get a response
+        let id = request.get("id").cloned();
+        let res = match (rpc_param(&request, "method").as_str(), &params) {
+            // positional parameters are valid JSON-RPC, but every method here takes named ones
+            (Some(_), Value::Array(_)) => Err(RpcError::invalid_params("Parameters must be named".into())),
+            (Some("solve"), Value::Object(_) | Value::Null) => {
+                queue.send((id, params)).expect("solve workers should run until the queue is closed");
+                continue;
+            }
+            (Some(method), Value::Object(_) | Value::Null) => rpc_call(pow, method, &params),
+            _ => {
+                rpc_respond(&id.unwrap_or(Value::Null), Err(RpcError::invalid_request()));
+                continue;
+            }
+        };
+        if let Some(id) = id {
+            rpc_respond(&id, res);
+        }
+    }
+    Ok(())
+}
+
+fn actual_main() -> Result<(), CliError> {
     let args: Vec<_> = std::env::args().collect();
     let name = args.first().map(|x| x as _).unwrap_or("kctf-pow");
-    if args.len() < 3 {
-        return Err(gen_usage(name));
+    if args.len() < 2 {
+        return Err(gen_usage(name).into());
     }
     let pow = KctfPow::new();
     match &args[1] as _ {
         "solve" => {
-            let chall = pow.decode_challenge(&args[2])?;
-            println!("{}", chall.solve());
+            let (checkpoint, threads, chall_strings) = parse_solve_args(&args[2..]).ok_or_else(|| gen_usage(name))?;
+            let threads: usize = match threads {
+                Some(threads) => threads.parse().map_err(|_| "Thread count is not a valid unsigned integer")?,
+                None => 0,
+            };
+            // with no challenges as arguments, read one per line from stdin
+            let chall_strings = if chall_strings.is_empty() {
+                std::io::stdin()
+                    .lines()
+                    .map(|line| line.map(|x| x.trim().to_string()))
+                    .filter(|line| !matches!(line, Ok(x) if x.is_empty()))
+                    .collect::<Result<Vec<_>, _>>()
+                    .map_err(|_| "Could not read from stdin")?
+            } else {
+                chall_strings.to_vec()
+            };
+            match (checkpoint, &chall_strings[..]) {
+                (Some(file), [chall_string]) => {
+                    if scheme_version(chall_string) == Hashcash::VERSION {
+                        return Err("Checkpoints are only supported for sloth challenges".into());
+                    }
+                    let chall = pow.decode_challenge(chall_string)?;
+                    println!("{}", solve_with_checkpoint(&pow, chall.params, file)?);
+                }
+                (Some(_), _) => return Err("Checkpoints are only supported when solving a single challenge".into()),
+                (None, _) => {
+                    for sol in solve_all(&pow, &chall_strings, threads)? {
+                        println!("{}", sol);
+                    }
+                }
+            }
         }
         "check" => {
-            let chall = pow.decode_challenge(&args[2])?;
-            let mut inp = String::new();
-            std::io::stdin().read_line(&mut inp).map_err(|_| "Could not read from stdin")?;
-            let res = chall.check(inp.trim())?;
-            if res {
-                println!("correct");
-            } else {
-                return Err("incorrect".into());
+            let (strict, chall_string) = match &args[2..] {
+                [flag, chall] if flag == "--strict" => (true, chall),
+                [chall] => (false, chall),
+                _ => return Err(gen_usage(name).into()),
+            };
+            report_check(check(&pow, chall_string, strict, read_line)?)?;
+        }
+        cmd @ ("gen" | "ask") => {
+            let ask = cmd == "ask";
+            let (seed, version, difficulty) = match parse_gen_args(&args[2..]) {
+                Some((Some(_), _, _)) if ask => return Err(gen_usage(name).into()),
+                Some(parsed) => parsed,
+                None => return Err(gen_usage(name).into()),
+            };
+            let seed = seed.map(derive_seed);
+            let difficulty: u32 = difficulty.parse().map_err(|_| "Difficulty is not a valid 32-bit unsigned integer")?;
+            match version {
+                KctfPow::VERSION => gen_and_ask(&pow, seed, difficulty, ask)?,
+                Hashcash::VERSION => gen_and_ask(&Hashcash, seed, difficulty, ask)?,
+                _ => return Err(format!("Unknown scheme {:?}", version).into()),
             }
         }
-        "gen" => {
-            let difficulty: u32 = args[2].parse().map_err(|_| "Difficulty is not a valid 32-bit unsigned integer")?;
-            println!("{}", pow.generate_challenge(difficulty));
-        }
-        "ask" => {
-            let difficulty: u32 = args[2].parse().map_err(|_| "Difficulty is not a valid 32-bit unsigned integer")?;
-            let chall = pow.generate_challenge(difficulty);
-            println!("{}", chall);
-            let mut inp = String::new();
-            std::io::stdin().read_line(&mut inp).map_err(|_| "Could not read from stdin")?;
-            let res = chall.check(inp.trim())?;
-            if res {
-                println!("correct");
-            } else {
-                return Err("incorrect".into());
+        "rpc" => {
+            if args.len() != 2 {
+                return Err(gen_usage(name).into());
             }
+            #[cfg(feature = "rpc")]
+            rpc(KctfPow::shared())?;
+            #[cfg(not(feature = "rpc"))]
+            return Err("This build doesn't support JSON-RPC, since it was built without the `rpc` feature".into());
         }
+        "timelock" => match &args[2..] {
+            [cmd, iterations] if cmd == "seal" => {
+                let iterations: u64 = iterations.parse().map_err(|_| "Iterations is not a valid 64-bit unsigned integer")?;
+                let mut payload = Vec::new();
+                std::io::stdin().read_to_end(&mut payload).map_err(|_| "Could not read from stdin")?;
+                println!("{}", TimeLock::seal(&payload, iterations));
+            }
+            [cmd, puzzle] if cmd == "open" => {
+                let payload = puzzle.parse::<TimeLock>()?.open()?;
+                std::io::stdout().write_all(&payload).map_err(|_| "Could not write to stdout")?;
+            }
+            _ => return Err(gen_usage(name).into()),
+        },
         _ => {
-            return Err(gen_usage(name));
+            return Err(gen_usage(name).into());
         }
     }
     Ok(())
 }
 
 fn main() {
-    if let Err(s) = actual_main() {
-        eprintln!("Error: {}", s);
-        std::process::exit(1);
+    if let Err(err) = actual_main() {
+        eprintln!("Error: {}", err);
+        std::process::exit(err.exit_code());
     }
 }
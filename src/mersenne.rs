@@ -0,0 +1,268 @@
+//! Fixed-width arithmetic modulo the Mersenne prime `2**1279 - 1`.
+//!
+//! Since kCTF's modulus is a Mersenne prime, reducing a product only needs a shift and an add instead of a division,
+//! and since the exponent is `2**1277`, every modular exponentiation in the solver is just 1277 squarings.
+
+use crate::integer::{self, Integer};
+use crate::{KctfPow, SqrtStrategy};
+use alloc::vec::Vec;
+
+/// The number of bits in the modulus.
+const BITS: u32 = 1279;
+/// The number of 64-bit limbs needed to hold a value of [`BITS`] bits with one spare bit.
+const LIMBS: usize = 20;
+/// The exponent of `2` that the solver raises values to.
+const SQRT_SQUARINGS: u32 = BITS - 2;
+/// The mask of the bits of the top limb that are below `2**1279`.
+const TOP_MASK: u64 = u64::MAX >> 1;
+
+/// An integer below `2**1280` that is operated on modulo `2**1279 - 1`.
+///
+/// Squaring always produces a fully reduced value, but flipping the low bit may produce the modulus itself,
+/// which is kept as-is so that the results exactly match the generic big integer path.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct Mersenne1279([u64; LIMBS]);
+
+impl Mersenne1279 {
+    /// Returns whether a proof-of-work system is the one that this kernel computes with.
+    pub fn matches(pow: &KctfPow) -> bool {
+        pow.sqrt == SqrtStrategy::Exponent
+            && pow.modulus == integer::mersenne(BITS)
+            && pow.exponent == (integer::mersenne(BITS) + 1u32) / 4u32
+    }
+
+    /// Reduces an arbitrary integer and loads it.
+    pub fn from_integer(val: &Integer) -> Self {
+        let reduced = integer::reduce(val, &integer::mersenne(BITS));
+        let bytes = integer::to_bytes(&reduced);
+        let mut limbs = [0; LIMBS];
+        for (i, chunk) in bytes.rchunks(8).enumerate() {
+            let mut limb = [0; 8];
+            limb[8 - chunk.len()..].copy_from_slice(chunk);
+            limbs[i] = u64::from_be_bytes(limb);
+        }
+        Self(limbs)
+    }
+
+    /// Loads a big-endian value below `2**1280` without reducing it, or returns `None` if it's too large.
+    ///
+    /// Unlike [`Mersenne1279::from_integer`], this doesn't allocate.
+    pub fn from_be_bytes(bytes: &[u8]) -> Option<Self> {
+        let start = bytes.iter().position(|&x| x != 0).unwrap_or(bytes.len());
+        let bytes = &bytes[start..];
+        if bytes.len() > LIMBS * 8 {
+            return None;
+        }
+        let mut limbs = [0; LIMBS];
+        for (i, chunk) in bytes.rchunks(8).enumerate() {
+            let mut limb = [0; 8];
+            limb[8 - chunk.len()..].copy_from_slice(chunk);
+            limbs[i] = u64::from_be_bytes(limb);
+        }
+        Some(Self(limbs))
+    }
+
+    /// Loads an integer below `2**1280` without reducing it, or returns `None` if it's too large.
+    ///
+    /// Unlike [`Mersenne1279::from_integer`], this doesn't allocate.
+    pub fn from_integer_unreduced(val: &Integer) -> Option<Self> {
+        let mut limbs = [0; LIMBS];
+        integer::to_limbs(val, &mut limbs).then_some(Self(limbs))
+    }
+
+    /// Returns the modulus minus the value, or `None` if the value is larger than the modulus.
+    pub fn negate_mod(self) -> Option<Self> {
+        if self.0[LIMBS - 1] > TOP_MASK {
+            return None;
+        }
+        // the modulus is all ones, so subtracting from it never borrows
+        let mut limbs = self.0;
+        for limb in limbs.iter_mut() {
+            *limb = !*limb;
+        }
+        limbs[LIMBS - 1] &= TOP_MASK;
+        Some(Self(limbs))
+    }
+
+    /// Converts the value back into an integer.
+    pub fn to_integer(self) -> Integer {
+        let bytes: Vec<u8> = self.0.iter().rev().flat_map(|x| x.to_be_bytes()).collect();
+        integer::from_bytes(&bytes)
+    }
+
+    /// Flips the lowest bit of the value.
+    pub fn flip_low_bit(&mut self) {
+        self.0[0] ^= 1;
+    }
+
+    /// Takes the modular square root of the value by raising it to the power of `2**1277`.
+    pub fn sqrt(&mut self) {
+        for _ in 0..SQRT_SQUARINGS {
+            self.square();
+        }
+    }
+
+    /// Squares the value and fully reduces it.
+    pub fn square(&mut self) {
+        let a = &self.0;
+        let mut prod = [0u64; 2 * LIMBS];
+        // the products of distinct limbs each appear twice, so compute them once then double
+        for i in 0..LIMBS {
+            let mut carry = 0u64;
+            for j in i + 1..LIMBS {
+                let v = a[i] as u128 * a[j] as u128 + prod[i + j] as u128 + carry as u128;
+                prod[i + j] = v as u64;
+                carry = (v >> 64) as u64;
+            }
+            prod[i + LIMBS] = carry;
+        }
+        let mut top = 0;
+        for limb in prod.iter_mut() {
+            let next = *limb >> 63;
+            *limb = (*limb << 1) | top;
+            top = next;
+        }
+        let mut carry = 0u64;
+        for i in 0..LIMBS {
+            let sq = a[i] as u128 * a[i] as u128;
+            let lo = prod[2 * i] as u128 + (sq as u64) as u128 + carry as u128;
+            prod[2 * i] = lo as u64;
+            let hi = prod[2 * i + 1] as u128 + (sq >> 64) + (lo >> 64);
+            prod[2 * i + 1] = hi as u64;
+            carry = (hi >> 64) as u64;
+        }
+        self.0 = Self::reduce(&prod);
+    }
+
+    /// Reduces a product of two values below `2**1280`.
+    fn reduce(prod: &[u64; 2 * LIMBS]) -> [u64; LIMBS] {
+        // since 2**1279 is 1 modulo the modulus, the bits above 1279 can be added onto the bits below
+        let mut folded = [0u64; LIMBS + 1];
+        let mut carry = 0u64;
+        for (k, limb) in folded.iter_mut().enumerate() {
+            let low = if k < LIMBS - 1 {
+                prod[k]
+            } else if k == LIMBS - 1 {
+                prod[k] & TOP_MASK
+            } else {
+                0
+            };
+            let high_hi = prod.get(LIMBS + k).copied().unwrap_or(0);
+            let high = (prod[LIMBS - 1 + k] >> 63) | (high_hi << 1);
+            let v = low as u128 + high as u128 + carry as u128;
+            *limb = v as u64;
+            carry = (v >> 64) as u64;
+        }
+        // the sum is below 2**1282, so a second fold leaves at most 2**1279 + 6
+        let mut res = [0u64; LIMBS];
+        res.copy_from_slice(&folded[..LIMBS]);
+        let mut carry = (res[LIMBS - 1] >> 63) | (folded[LIMBS] << 1);
+        res[LIMBS - 1] &= TOP_MASK;
+        for limb in res.iter_mut() {
+            let (v, overflow) = limb.overflowing_add(carry);
+            *limb = v;
+            carry = overflow as u64;
+        }
+        // the value is at least the modulus exactly when adding 1 reaches 2**1279,
+        // in which case subtracting the modulus is adding 1 and clearing that bit
+        let mut plus_one = res;
+        let mut carry = 1u64;
+        for limb in plus_one.iter_mut() {
+            let (v, overflow) = limb.overflowing_add(carry);
+            *limb = v;
+            carry = overflow as u64;
+        }
+        if plus_one[LIMBS - 1] >> 63 == 1 {
+            plus_one[LIMBS - 1] &= TOP_MASK;
+            plus_one
+        } else {
+            res
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::vec;
+    use rand::{RngCore, SeedableRng};
+    use rand_chacha::ChaCha20Rng;
+
+    /// Values below `2**1280` that are at or near the edges of what the kernel handles, followed by random ones.
+    fn values() -> Vec<Integer> {
+        let modulus = integer::mersenne(BITS);
+        let mut vals = vec![
+            Integer::from(0u32),
+            Integer::from(1u32),
+            Integer::from(2u32),
+            modulus.clone() - 1u32,
+            modulus.clone(),
+            modulus.clone() + 1u32,
+            modulus.clone() + 2u32,
+            integer::mersenne(BITS + 1),
+        ];
+        let mut rng = ChaCha20Rng::seed_from_u64(1279);
+        for len in [8, 80, 159, 160, 160, 160] {
+            let mut bytes = vec![0; len];
+            rng.fill_bytes(&mut bytes);
+            vals.push(integer::from_bytes(&bytes));
+        }
+        vals
+    }
+
+    fn load(val: &Integer) -> Mersenne1279 {
+        Mersenne1279::from_integer_unreduced(val).expect("value should be below 2**1280")
+    }
+
+    #[test]
+    fn square_matches_generic() {
+        let modulus = integer::mersenne(BITS);
+        for val in values() {
+            let mut fast = load(&val);
+            let mut generic = val.clone();
+            // square repeatedly so that the kernel's own outputs are fed back into it
+            for _ in 0..4 {
+                fast.square();
+                integer::square_mod(&mut generic, &modulus);
+                assert_eq!(fast.to_integer(), generic, "squaring {:?}", val);
+            }
+        }
+    }
+
+    #[test]
+    fn sqrt_matches_generic() {
+        let modulus = integer::mersenne(BITS);
+        let exponent = (modulus.clone() + 1u32) / 4u32;
+        for val in values() {
+            let mut fast = load(&val);
+            fast.sqrt();
+            let mut generic = val.clone();
+            integer::pow_mod(&mut generic, &exponent, &modulus);
+            assert_eq!(fast.to_integer(), generic, "taking the root of {:?}", val);
+        }
+    }
+
+    #[test]
+    fn loading_matches_generic() {
+        let modulus = integer::mersenne(BITS);
+        for val in values() {
+            let bytes = integer::to_bytes(&val);
+            assert_eq!(Mersenne1279::from_be_bytes(&bytes), Some(load(&val)));
+            assert_eq!(load(&val).to_integer(), val);
+            assert_eq!(
+                Mersenne1279::from_integer(&val).to_integer(),
+                integer::reduce(&val, &modulus)
+            );
+            assert_eq!(
+                load(&val).negate_mod().map(Mersenne1279::to_integer),
+                integer::negate_mod(&val, &modulus)
+            );
+        }
+        let too_large = integer::mersenne(BITS + 1) + 1u32;
+        assert_eq!(Mersenne1279::from_integer_unreduced(&too_large), None);
+        assert_eq!(
+            Mersenne1279::from_be_bytes(&integer::to_bytes(&too_large)),
+            None
+        );
+    }
+}
@@ -0,0 +1,126 @@
+//! Primality testing and prime generation.
+
+use crate::integer::{self, Integer};
+use alloc::vec;
+use rand::RngCore;
+
+/// Small primes used for trial division and as Miller-Rabin bases.
+const SMALL_PRIMES: [u32; 25] = [
+    2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37, 41, 43, 47, 53, 59, 61, 67, 71, 73, 79, 83, 89, 97,
+];
+
+/// Returns whether a number is probably prime, using trial division then Miller-Rabin with small prime bases.
+pub(crate) fn is_probable_prime(n: &Integer) -> bool {
+    let one = Integer::from(1u32);
+    if *n <= one {
+        return false;
+    }
+    for &p in SMALL_PRIMES.iter() {
+        let p = Integer::from(p);
+        if *n == p {
+            return true;
+        }
+        if integer::reduce(n, &p) == Integer::from(0u32) {
+            return false;
+        }
+    }
+    let n_minus_one = n.clone() - 1u32;
+    let mut odd_part = n_minus_one.clone();
+    let mut two_adicity = 0;
+    while !integer::is_odd(&odd_part) {
+        odd_part /= 2u32;
+        two_adicity += 1;
+    }
+    'bases: for &base in SMALL_PRIMES.iter() {
+        let mut x = Integer::from(base);
+        integer::pow_mod(&mut x, &odd_part, n);
+        if x == one || x == n_minus_one {
+            continue;
+        }
+        for _ in 1..two_adicity {
+            integer::square_mod(&mut x, n);
+            if x == n_minus_one {
+                continue 'bases;
+            }
+        }
+        return false;
+    }
+    true
+}
+
+/// Generates a random prime with exactly `bits` bits, where `bits` is at least 2.
+///
+/// The top two bits are always set, so that the product of two such primes has exactly twice as many bits.
+pub(crate) fn random_prime<R: RngCore>(rng: &mut R, bits: u32) -> Integer {
+    let len = bits.div_ceil(8) as usize;
+    let mut bytes = vec![0; len];
+    loop {
+        rng.fill_bytes(&mut bytes);
+        // clear the bits above the top bit, then set the top two bits and the bottom bit
+        let excess = len as u32 * 8 - bits;
+        bytes[0] &= 0xff >> excess;
+        let mut candidate = integer::from_bytes(&bytes);
+        candidate |= Integer::from(3u32) << (bits - 2);
+        candidate |= Integer::from(1u32);
+        if is_probable_prime(&candidate) {
+            return candidate;
+        }
+    }
+}
+
+/// The fewest bits that [`random_prime_pair`] can make a modulus with, since there's only one 4-bit prime with its top two bits set.
+pub(crate) const MIN_PAIR_BITS: u32 = 10;
+
+/// Generates two different random primes whose product has exactly `bits` bits, where `bits` is at least [`MIN_PAIR_BITS`].
+///
+/// If the primes were the same, the product would be a square, which is easy to take the root of and has a different totient.
+pub(crate) fn random_prime_pair<R: RngCore>(rng: &mut R, bits: u32) -> (Integer, Integer) {
+    let p = random_prime(rng, bits / 2);
+    loop {
+        let q = random_prime(rng, bits - bits / 2);
+        if q != p {
+            return (p, q);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha20Rng;
+
+    fn is_prime_by_division(n: u32) -> bool {
+        n >= 2
+            && (2..n)
+                .take_while(|d| d * d <= n)
+                .all(|d| !n.is_multiple_of(d))
+    }
+
+    #[test]
+    fn matches_trial_division() {
+        for n in 0..20000u32 {
+            assert_eq!(
+                is_probable_prime(&Integer::from(n)),
+                is_prime_by_division(n),
+                "testing {}",
+                n
+            );
+        }
+    }
+
+    #[test]
+    fn random_primes_have_exact_bits() {
+        let mut rng = ChaCha20Rng::seed_from_u64(69);
+        for bits in [2, 3, 8, 31, 64, 100] {
+            let p = random_prime(&mut rng, bits);
+            assert!(is_probable_prime(&p));
+            assert!(
+                p >= Integer::from(3u32) << (bits - 2),
+                "generating {} bits",
+                bits
+            );
+            assert!(p < Integer::from(1u32) << bits, "generating {} bits", bits);
+        }
+    }
+}
@@ -0,0 +1,57 @@
+//! Python bindings built with [`pyo3`], which can be imported in place of kCTF's `pow.py`.
+//!
+//! The functions have the same names and arguments as in `pow.py`, and accept and produce challenges and solutions that are
+//! interchangeable with it. Invalid challenges and solutions raise `ValueError`.
+//!
+//! ```python
+//! import kctf_pow
+//!
+//! chal = kctf_pow.get_challenge(100)
+//! sol = kctf_pow.solve_challenge(chal)
+//! assert kctf_pow.verify_challenge(chal, sol)
+//! ```
+
+use crate::{ChallengeParams, KctfPow, PowError};
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+
+impl From<PowError> for PyErr {
+    fn from(err: PowError) -> Self {
+        PyValueError::new_err(err.to_string())
+    }
+}
+
+/// Generates a random challenge given a difficulty.
+#[pyfunction]
+fn get_challenge(diff: u32) -> String {
+    ChallengeParams::generate_challenge(diff).to_string()
+}
+
+/// Solves a challenge and returns the solution.
+///
+/// Other Python threads can run while the challenge is being solved.
+#[pyfunction]
+fn solve_challenge(py: Python<'_>, chal: &str) -> PyResult<String> {
+    let params = ChallengeParams::decode_challenge(chal)?;
+    Ok(py.detach(|| params.solve(KctfPow::shared())).to_string())
+}
+
+/// Checks a solution to see if it satisfies a challenge.
+///
+/// Bypass tokens aren't supported, so `allow_bypass` is only accepted for compatibility with `pow.py`.
+#[pyfunction]
+#[pyo3(signature = (chal, sol, allow_bypass = true))]
+fn verify_challenge(chal: &str, sol: &str, allow_bypass: bool) -> PyResult<bool> {
+    let _ = allow_bypass;
+    let params = ChallengeParams::decode_challenge(chal)?;
+    Ok(params.check(KctfPow::shared(), sol)?)
+}
+
+/// The `kctf_pow` Python module.
+#[pymodule]
+fn kctf_pow(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_function(wrap_pyfunction!(get_challenge, m)?)?;
+    m.add_function(wrap_pyfunction!(solve_challenge, m)?)?;
+    m.add_function(wrap_pyfunction!(verify_challenge, m)?)?;
+    Ok(())
+}
@@ -0,0 +1,123 @@
+//! A common interface to the proof-of-work schemes in this crate, so that code can be written once for all of them.
+
+use crate::{ChallengeParams, KctfPow, PowError, Solution};
+use alloc::string::{String, ToString};
+use rand::prelude::*;
+
+/// A proof-of-work scheme, which generates, encodes, decodes, solves, and checks its own challenges and solutions.
+///
+/// Each scheme has its own version prefix, which is the part of its challenge and solution strings before the first `.`,
+/// so the scheme of a challenge string can be found with [`scheme_version`].
+///
+/// ```rust
+/// use kctf_pow::{Hashcash, KctfPow, PowScheme};
+///
+/// # #[cfg(feature = "std")]
+/// fn solve_and_check<S: PowScheme>(scheme: &S, difficulty: u32) -> bool {
+///     let chall = scheme.generate(difficulty).unwrap();
+///     let chall = scheme.decode_challenge(&scheme.encode_challenge(&chall)).unwrap();
+///     let sol = scheme.solve(&chall);
+///     scheme.check(&chall, &scheme.decode_solution(&scheme.encode_solution(&sol)).unwrap())
+/// }
+///
+/// # #[cfg(feature = "std")] {
+/// assert!(solve_and_check(&KctfPow::new(), 10));
+/// assert!(solve_and_check(&Hashcash, 10));
+/// # }
+/// ```
+pub trait PowScheme {
+    /// The parameters of a challenge.
+    type Challenge;
+    /// The solution to a challenge.
+    type Solution;
+
+    /// The version prefix of the scheme's challenge and solution strings.
+    const VERSION: &'static str;
+
+    /// Generates a random challenge given a difficulty.
+    ///
+    /// Returns [`PowError::DifficultyTooLarge`] if the scheme can't have challenges that difficult.
+    #[cfg(feature = "std")]
+    fn generate(&self, difficulty: u32) -> Result<Self::Challenge, PowError> {
+        self.generate_with_rng(&mut thread_rng(), difficulty)
+    }
+
+    /// Generates a random challenge given a difficulty, using a specific random number generator.
+    ///
+    /// Returns [`PowError::DifficultyTooLarge`] if the scheme can't have challenges that difficult.
+    fn generate_with_rng<R: CryptoRng + RngCore>(
+        &self,
+        rng: &mut R,
+        difficulty: u32,
+    ) -> Result<Self::Challenge, PowError>;
+
+    /// Encodes a challenge into a string.
+    fn encode_challenge(&self, chall: &Self::Challenge) -> String;
+
+    /// Decodes a challenge from a string.
+    fn decode_challenge(&self, chall_string: &str) -> Result<Self::Challenge, PowError>;
+
+    /// Encodes a solution into a string.
+    fn encode_solution(&self, sol: &Self::Solution) -> String;
+
+    /// Decodes a solution from a string.
+    fn decode_solution(&self, sol_string: &str) -> Result<Self::Solution, PowError>;
+
+    /// Solves a challenge and returns the solution.
+    fn solve(&self, chall: &Self::Challenge) -> Self::Solution;
+
+    /// Checks a solution to see if it satisfies a challenge.
+    fn check(&self, chall: &Self::Challenge, sol: &Self::Solution) -> bool;
+}
+
+/// The sloth scheme used by kCTF.
+///
+/// Note that [`KctfPow::decode_challenge`] and [`KctfPow::generate_challenge_with_rng`] return a [`Challenge`](crate::Challenge),
+/// while the methods of this trait work with [`ChallengeParams`] directly.
+impl PowScheme for KctfPow {
+    type Challenge = ChallengeParams;
+    type Solution = Solution;
+
+    const VERSION: &'static str = crate::VERSION;
+
+    fn generate_with_rng<R: CryptoRng + RngCore>(
+        &self,
+        rng: &mut R,
+        difficulty: u32,
+    ) -> Result<ChallengeParams, PowError> {
+        Ok(ChallengeParams::generate_challenge_with_rng(
+            rng, difficulty,
+        ))
+    }
+
+    fn encode_challenge(&self, chall: &ChallengeParams) -> String {
+        chall.to_string()
+    }
+
+    fn decode_challenge(&self, chall_string: &str) -> Result<ChallengeParams, PowError> {
+        ChallengeParams::decode_challenge(chall_string)
+    }
+
+    fn encode_solution(&self, sol: &Solution) -> String {
+        sol.to_string()
+    }
+
+    fn decode_solution(&self, sol_string: &str) -> Result<Solution, PowError> {
+        Solution::decode_solution(sol_string)
+    }
+
+    fn solve(&self, chall: &ChallengeParams) -> Solution {
+        chall.clone().solve(self)
+    }
+
+    fn check(&self, chall: &ChallengeParams, sol: &Solution) -> bool {
+        sol.check(chall, self)
+    }
+}
+
+/// Returns the version prefix of a challenge or solution string, which is everything before the first `.`.
+///
+/// This can be compared against [`PowScheme::VERSION`] to pick the scheme to decode a string with.
+pub fn scheme_version(string: &str) -> &str {
+    string.split('.').next().unwrap_or_default()
+}
@@ -0,0 +1,227 @@
+//! [`serde`] support for challenges and solutions.
+
+use crate::{integer, ChallengeParams, Solution};
+use alloc::string::String;
+use alloc::vec::Vec;
+use base64::prelude::*;
+use core::fmt;
+use core::marker::PhantomData;
+use core::str::FromStr;
+use serde::de::{self, MapAccess, SeqAccess, Visitor};
+use serde::ser::SerializeStruct;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+/// Serializes challenges and solutions in their structured form.
+///
+/// By default, challenges and solutions are serialized in the same string form as their [`Display`](core::fmt::Display) implementations.
+/// Use this module with `#[serde(with = "kctf_pow::serde_structured")]` to serialize them as structures
+/// with the difficulty and base64-encoded value as separate fields instead.
+/// In self-describing formats such as JSON, the default deserializer accepts either form.
+///
+/// ```rust
+/// use kctf_pow::{ChallengeParams, Solution};
+///
+/// #[derive(serde::Serialize, serde::Deserialize)]
+/// struct Issued {
+///     chall: ChallengeParams,
+///     #[serde(with = "kctf_pow::serde_structured")]
+///     sol: Solution,
+/// }
+///
+/// let chall: ChallengeParams = "s.AAAAMg==.NDtqORW1uZlIgzszbdMGZA==".parse().unwrap();
+/// let sol: Solution = "s.AQI=".parse().unwrap();
+/// let json = serde_json::to_string(&Issued { chall: chall.clone(), sol: sol.clone() }).unwrap();
+/// assert_eq!(json, r#"{"chall":"s.AAAAMg==.NDtqORW1uZlIgzszbdMGZA==","sol":{"value":"AQI="}}"#);
+/// // the string form is the same as the Display implementation
+/// assert_eq!(serde_json::to_value(&sol).unwrap(), sol.to_string());
+///
+/// let issued: Issued = serde_json::from_str(&json).unwrap();
+/// assert_eq!(issued.chall, chall);
+/// assert_eq!(issued.sol, sol);
+/// let chall: ChallengeParams =
+///     serde_json::from_str(r#"{"difficulty":50,"value":"NDtqORW1uZlIgzszbdMGZA=="}"#).unwrap();
+/// assert_eq!(chall, issued.chall);
+/// ```
+pub mod serde_structured {
+    use super::{EitherVisitor, Fields};
+    use core::marker::PhantomData;
+    use serde::{Deserializer, Serializer};
+
+    /// Types that have a structured serialized form.
+    ///
+    /// This trait is sealed and can't be implemented outside of this crate.
+    pub trait Structured: Fields {}
+
+    impl Structured for crate::ChallengeParams {}
+    impl Structured for crate::Solution {}
+
+    /// Serializes a challenge or solution as a structure.
+    pub fn serialize<T: Structured, S: Serializer>(
+        val: &T,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        val.serialize_fields(serializer)
+    }
+
+    /// Deserializes a challenge or solution from its structured form.
+    pub fn deserialize<'de, T: Structured, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<T, D::Error> {
+        deserializer.deserialize_struct(T::NAME, T::FIELDS, EitherVisitor(PhantomData))
+    }
+}
+
+/// Conversion between a value and the fields of its structured form.
+///
+/// This is public so that it can bound [`serde_structured::Structured`], but is in a private module so it can't be implemented elsewhere.
+pub trait Fields: FromStr<Err = crate::PowError> {
+    #[doc(hidden)]
+    const NAME: &'static str;
+    #[doc(hidden)]
+    const FIELDS: &'static [&'static str];
+
+    #[doc(hidden)]
+    fn serialize_fields<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error>;
+
+    #[doc(hidden)]
+    fn from_fields(difficulty: Option<u32>, val: Option<Vec<u8>>) -> Result<Self, &'static str>;
+}
+
+impl Fields for ChallengeParams {
+    const NAME: &'static str = "ChallengeParams";
+    const FIELDS: &'static [&'static str] = &["difficulty", "value"];
+
+    fn serialize_fields<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut state = serializer.serialize_struct(Self::NAME, 2)?;
+        state.serialize_field("difficulty", &self.difficulty)?;
+        state.serialize_field(
+            "value",
+            &BASE64_STANDARD.encode(integer::to_bytes(&self.val)),
+        )?;
+        state.end()
+    }
+
+    fn from_fields(difficulty: Option<u32>, val: Option<Vec<u8>>) -> Result<Self, &'static str> {
+        Ok(Self {
+            difficulty: difficulty.ok_or("difficulty")?,
+            val: integer::from_bytes(&val.ok_or("value")?),
+        })
+    }
+}
+
+impl Fields for Solution {
+    const NAME: &'static str = "Solution";
+    const FIELDS: &'static [&'static str] = &["value"];
+
+    fn serialize_fields<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut state = serializer.serialize_struct(Self::NAME, 1)?;
+        state.serialize_field("value", &BASE64_STANDARD.encode(self.to_bytes()))?;
+        state.end()
+    }
+
+    fn from_fields(_: Option<u32>, val: Option<Vec<u8>>) -> Result<Self, &'static str> {
+        Ok(Self::from_bytes(&val.ok_or("value")?))
+    }
+}
+
+/// Deserializes either the string form or the structured form.
+struct EitherVisitor<T>(PhantomData<T>);
+
+impl<T: Fields> EitherVisitor<T> {
+    fn has_difficulty() -> bool {
+        T::FIELDS.contains(&"difficulty")
+    }
+
+    fn decode_value<E: de::Error>(encoded: &str) -> Result<Vec<u8>, E> {
+        BASE64_STANDARD
+            .decode(encoded)
+            .map_err(|_| E::invalid_value(de::Unexpected::Str(encoded), &"a base64 string"))
+    }
+}
+
+impl<'de, T: Fields> Visitor<'de> for EitherVisitor<T> {
+    type Value = T;
+
+    fn expecting(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(fmt, "a string or structure representing a {}", T::NAME)
+    }
+
+    fn visit_str<E: de::Error>(self, s: &str) -> Result<T, E> {
+        s.parse().map_err(E::custom)
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<T, A::Error> {
+        let difficulty = if Self::has_difficulty() {
+            Some(
+                seq.next_element()?
+                    .ok_or_else(|| de::Error::invalid_length(0, &self))?,
+            )
+        } else {
+            None
+        };
+        let encoded: String = seq
+            .next_element()?
+            .ok_or_else(|| de::Error::invalid_length(T::FIELDS.len() - 1, &self))?;
+        T::from_fields(difficulty, Some(Self::decode_value(&encoded)?))
+            .map_err(de::Error::missing_field)
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<T, A::Error> {
+        let mut difficulty = None;
+        let mut val = None;
+        while let Some(key) = map.next_key::<String>()? {
+            match &key as &str {
+                "difficulty" if Self::has_difficulty() => {
+                    if difficulty.is_some() {
+                        return Err(de::Error::duplicate_field("difficulty"));
+                    }
+                    difficulty = Some(map.next_value()?);
+                }
+                "value" => {
+                    if val.is_some() {
+                        return Err(de::Error::duplicate_field("value"));
+                    }
+                    let encoded: String = map.next_value()?;
+                    val = Some(Self::decode_value(&encoded)?);
+                }
+                _ => return Err(de::Error::unknown_field(&key, T::FIELDS)),
+            }
+        }
+        T::from_fields(difficulty, val).map_err(de::Error::missing_field)
+    }
+}
+
+/// Deserializes the string form, or in self-describing formats, either form.
+fn deserialize_either<'de, T: Fields, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<T, D::Error> {
+    if deserializer.is_human_readable() {
+        deserializer.deserialize_any(EitherVisitor(PhantomData))
+    } else {
+        deserializer.deserialize_str(EitherVisitor(PhantomData))
+    }
+}
+
+impl Serialize for ChallengeParams {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl Serialize for Solution {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for ChallengeParams {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserialize_either(deserializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Solution {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserialize_either(deserializer)
+    }
+}
@@ -0,0 +1,253 @@
+//! The sloth function's square roots under each [`SqrtStrategy`].
+
+use crate::integer::{self, Integer};
+use crate::{KctfPow, SqrtStrategy};
+
+/// Returns whether a reduced value is a square modulo an odd prime, given `(modulus - 1) / 2`.
+fn is_square(val: &Integer, modulus: &Integer, legendre_exp: &Integer) -> bool {
+    let mut res = val.clone();
+    integer::pow_mod(&mut res, legendre_exp, modulus);
+    *val == Integer::from(0u32) || res == Integer::from(1u32)
+}
+
+/// Returns the smallest value that isn't a square modulo an odd prime.
+pub(crate) fn find_non_residue(modulus: &Integer) -> Integer {
+    let legendre_exp = (modulus.clone() - 1u32) / 2u32;
+    let mut candidate = Integer::from(2u32);
+    while is_square(&candidate, modulus, &legendre_exp) {
+        candidate += 1u32;
+    }
+    candidate
+}
+
+/// The sloth function of a proof-of-work system, with everything needed to take square roots precomputed.
+pub(crate) enum Sloth<'a> {
+    Exponent(&'a KctfPow),
+    TonelliShanks(TonelliShanks<'a>),
+}
+
+impl<'a> Sloth<'a> {
+    pub fn new(pow: &'a KctfPow) -> Self {
+        match &pow.sqrt {
+            SqrtStrategy::Exponent => Sloth::Exponent(pow),
+            SqrtStrategy::TonelliShanks { non_residue } => {
+                Sloth::TonelliShanks(TonelliShanks::new(&pow.modulus, non_residue))
+            }
+        }
+    }
+
+    /// Does one iteration of solving.
+    pub fn root_step(&self, val: &mut Integer) {
+        match self {
+            Sloth::Exponent(pow) => {
+                integer::pow_mod(val, &pow.exponent, &pow.modulus);
+                integer::flip_low_bit(val);
+            }
+            Sloth::TonelliShanks(ts) => ts.root_step(val),
+        }
+    }
+
+    /// Does one iteration of checking, undoing one iteration of solving.
+    pub fn square_step(&self, val: &mut Integer) {
+        match self {
+            Sloth::Exponent(pow) => {
+                integer::flip_low_bit(val);
+                integer::square_mod(val, &pow.modulus);
+            }
+            Sloth::TonelliShanks(ts) => ts.square_step(val),
+        }
+    }
+
+    /// Returns whether checking a solution ended up back at the starting value of a challenge.
+    pub fn matches(&self, start: &Integer, end: &Integer) -> bool {
+        match self {
+            // the sign of the starting value is lost by the last square
+            Sloth::Exponent(pow) => {
+                start == end || integer::negate_mod(start, &pow.modulus).as_ref() == Some(end)
+            }
+            Sloth::TonelliShanks(ts) => {
+                integer::reduce(start, ts.modulus) == integer::reduce(end, ts.modulus)
+            }
+        }
+    }
+}
+
+/// Square roots using the Tonelli-Shanks algorithm.
+///
+/// Roots are always taken of a square: either the value itself, or the value times the non-residue.
+/// The parity of the root records which one it was, and flipping the low bit leaves `modulus - 1` alone,
+/// so that each iteration can be exactly undone.
+pub(crate) struct TonelliShanks<'a> {
+    modulus: &'a Integer,
+    modulus_minus_one: Integer,
+    non_residue: &'a Integer,
+    non_residue_inv: Integer,
+    legendre_exp: Integer,
+    odd_part: Integer,
+    two_adicity: u32,
+    root_exp: Integer,
+    odd_part_root: Integer,
+}
+
+impl<'a> TonelliShanks<'a> {
+    fn new(modulus: &'a Integer, non_residue: &'a Integer) -> Self {
+        let modulus_minus_one = modulus.clone() - 1u32;
+        let mut odd_part = modulus_minus_one.clone();
+        let mut two_adicity = 0;
+        while !integer::is_odd(&odd_part) {
+            odd_part /= 2u32;
+            two_adicity += 1;
+        }
+        let mut non_residue_inv = non_residue.clone();
+        integer::pow_mod(&mut non_residue_inv, &(modulus.clone() - 2u32), modulus);
+        let mut odd_part_root = non_residue.clone();
+        integer::pow_mod(&mut odd_part_root, &odd_part, modulus);
+        Self {
+            modulus,
+            legendre_exp: modulus_minus_one.clone() / 2u32,
+            modulus_minus_one,
+            non_residue,
+            non_residue_inv,
+            root_exp: (odd_part.clone() + 1u32) / 2u32,
+            odd_part,
+            two_adicity,
+            odd_part_root,
+        }
+    }
+
+    /// Returns a square root of a reduced value, if it has one.
+    fn sqrt(&self, val: &Integer) -> Option<Integer> {
+        let one = Integer::from(1u32);
+        if *val == Integer::from(0u32) {
+            return Some(val.clone());
+        }
+        if !is_square(val, self.modulus, &self.legendre_exp) {
+            return None;
+        }
+        let mut m = self.two_adicity;
+        let mut c = self.odd_part_root.clone();
+        let mut t = val.clone();
+        integer::pow_mod(&mut t, &self.odd_part, self.modulus);
+        let mut root = val.clone();
+        integer::pow_mod(&mut root, &self.root_exp, self.modulus);
+        while t != one {
+            // find the least i such that t**(2**i) is 1, which is below m since val is a square
+            let mut i = 0;
+            let mut t_pow = t.clone();
+            while t_pow != one {
+                integer::square_mod(&mut t_pow, self.modulus);
+                i += 1;
+            }
+            let mut b = c;
+            for _ in 0..m - i - 1 {
+                integer::square_mod(&mut b, self.modulus);
+            }
+            m = i;
+            c = integer::mul_mod(&b, &b, self.modulus);
+            t = integer::mul_mod(&t, &c, self.modulus);
+            root = integer::mul_mod(&root, &b, self.modulus);
+        }
+        Some(root)
+    }
+
+    /// Flips the low bit of a reduced value, unless that would make it equal to the modulus.
+    fn flip_in_range(&self, val: &mut Integer) {
+        if *val != self.modulus_minus_one {
+            integer::flip_low_bit(val);
+        }
+    }
+
+    fn root_step(&self, val: &mut Integer) {
+        let reduced = integer::reduce(val, self.modulus);
+        let (root, want_odd) = match self.sqrt(&reduced) {
+            Some(root) => (root, false),
+            None => {
+                let square = integer::mul_mod(&reduced, self.non_residue, self.modulus);
+                let root = self
+                    .sqrt(&square)
+                    .expect("a non-residue times a non-residue should be a square");
+                (root, true)
+            }
+        };
+        // the roots are r and modulus - r, which have different parities since the modulus is odd
+        *val = if integer::is_odd(&root) == want_odd {
+            root
+        } else {
+            integer::negate_mod(&root, self.modulus).expect("root should be reduced")
+        };
+        self.flip_in_range(val);
+    }
+
+    fn square_step(&self, val: &mut Integer) {
+        let mut root = integer::reduce(val, self.modulus);
+        self.flip_in_range(&mut root);
+        let odd = integer::is_odd(&root);
+        integer::square_mod(&mut root, self.modulus);
+        *val = if odd {
+            integer::mul_mod(&root, &self.non_residue_inv, self.modulus)
+        } else {
+            root
+        };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::vec::Vec;
+
+    /// Odd primes that are 1 mod 4, with various powers of two dividing `p - 1`.
+    const PRIMES: [u32; 6] = [5, 13, 17, 41, 97, 257];
+
+    #[test]
+    fn sqrt_finds_every_root() {
+        for p in PRIMES {
+            let modulus = Integer::from(p);
+            let non_residue = find_non_residue(&modulus);
+            let ts = TonelliShanks::new(&modulus, &non_residue);
+            let squares: Vec<u32> = (0..p).map(|x| x * x % p).collect();
+            for val in 0..p {
+                match ts.sqrt(&Integer::from(val)) {
+                    Some(root) => {
+                        let square = integer::mul_mod(&root, &root, &modulus);
+                        assert_eq!(
+                            square,
+                            Integer::from(val),
+                            "taking the root of {} mod {}",
+                            val,
+                            p
+                        );
+                    }
+                    None => assert!(
+                        !squares.contains(&val),
+                        "taking the root of {} mod {}",
+                        val,
+                        p
+                    ),
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn square_step_undoes_root_step() {
+        for p in PRIMES {
+            let modulus = Integer::from(p);
+            let non_residue = find_non_residue(&modulus);
+            let ts = TonelliShanks::new(&modulus, &non_residue);
+            let mut roots = Vec::new();
+            for val in 0..p {
+                let mut root = Integer::from(val);
+                ts.root_step(&mut root);
+                assert!(root < modulus, "solving {} mod {}", val, p);
+                roots.push(root.clone());
+                ts.square_step(&mut root);
+                assert_eq!(root, Integer::from(val), "checking {} mod {}", val, p);
+            }
+            // every value has its own root, so no two challenges share a solution
+            roots.sort();
+            roots.dedup();
+            assert_eq!(roots.len(), p as usize, "solving mod {}", p);
+        }
+    }
+}
@@ -0,0 +1,199 @@
+//! Rivest-Shamir-Wagner time-lock puzzles, which encrypt a payload so that it can only be decrypted after a number of sequential squarings.
+//!
+//! Whoever seals a puzzle knows the factors of its modulus, so sealing is fast no matter how many iterations there are.
+//! Opening a puzzle takes `iterations` modular squarings, which can't be done in parallel.
+//!
+//! ```rust
+//! use kctf_pow::TimeLock;
+//!
+//! # #[cfg(feature = "std")] {
+//! let puzzle = TimeLock::seal(b"flag{patience}", 1000);
+//! let puzzle: TimeLock = puzzle.to_string().parse().unwrap();
+//! assert_eq!(puzzle.open().unwrap(), b"flag{patience}");
+//! # }
+//! ```
+
+use crate::integer::{self, Integer};
+use crate::{decode_parts, decode_u64, prime, PowError};
+use alloc::vec::Vec;
+use base64::prelude::*;
+use core::fmt;
+use core::str::FromStr;
+use rand::prelude::*;
+use sha2::{Digest, Sha256};
+
+const VERSION: &str = "t";
+/// The number of bits in the modulus of a sealed puzzle.
+const DEFAULT_MODULUS_BITS: u32 = 2048;
+/// Separates the hashes used to derive the keystream from any other use of SHA-256 on the same values.
+const KEY_DOMAIN: &[u8] = b"kctf-pow timelock key";
+/// Separates the hashes used for the authentication tag from any other use of SHA-256 on the same values.
+const TAG_DOMAIN: &[u8] = b"kctf-pow timelock tag";
+/// The number of bytes in the authentication tag at the end of the ciphertext.
+const TAG_LEN: usize = 32;
+
+/// A sealed time-lock puzzle.
+///
+/// If you want to serialize it to a string, use the [`Display`](core::fmt::Display) implementation.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct TimeLock {
+    /// The number of squarings needed to open the puzzle.
+    pub iterations: u64,
+    /// The RSA modulus that squaring is done modulo.
+    pub modulus: Integer,
+    /// The starting value, which is squared `iterations` times to get the key.
+    pub val: Integer,
+    /// The encrypted payload, followed by a 32-byte authentication tag.
+    pub ciphertext: Vec<u8>,
+}
+
+impl TimeLock {
+    /// Seals a payload into a puzzle that takes `iterations` squarings to open, using a 2048-bit modulus.
+    ///
+    /// How long the squarings take depends on the machine opening the puzzle, so `iterations` should be calibrated
+    /// by timing [`TimeLock::open`] on a fast machine.
+    #[cfg(feature = "std")]
+    pub fn seal(payload: &[u8], iterations: u64) -> TimeLock {
+        Self::seal_with_rng(&mut thread_rng(), payload, iterations)
+    }
+
+    /// Seals a payload into a puzzle that takes `iterations` squarings to open, using a 2048-bit modulus and a specific random number generator.
+    pub fn seal_with_rng<R: CryptoRng + RngCore>(
+        rng: &mut R,
+        payload: &[u8],
+        iterations: u64,
+    ) -> TimeLock {
+        Self::seal_with_bits(rng, payload, iterations, DEFAULT_MODULUS_BITS)
+    }
+
+    /// Seals a payload into a puzzle that takes `iterations` squarings to open, using a modulus that is `modulus_bits` bits long.
+    ///
+    /// Moduli shorter than 2048 bits can be factored more easily, which lets the puzzle be opened without doing the squarings.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `modulus_bits` is less than 10, since smaller moduli can't be the product of two different primes of about the same size.
+    pub fn seal_with_bits<R: CryptoRng + RngCore>(
+        rng: &mut R,
+        payload: &[u8],
+        iterations: u64,
+        modulus_bits: u32,
+    ) -> TimeLock {
+        assert!(
+            modulus_bits >= prime::MIN_PAIR_BITS,
+            "modulus must have at least 10 bits"
+        );
+        let (p, q) = prime::random_prime_pair(rng, modulus_bits);
+        let modulus = p.clone() * q.clone();
+        let totient = (p - 1u32) * (q - 1u32);
+        let val = integer::random_below(rng, &modulus);
+        // the trapdoor: squaring `iterations` times is raising to 2**iterations, which can be reduced by the totient
+        let mut exponent = Integer::from(2u32);
+        integer::pow_mod(&mut exponent, &Integer::from(iterations), &totient);
+        let mut key = val.clone();
+        integer::pow_mod(&mut key, &exponent, &modulus);
+        let mut ciphertext = payload.to_vec();
+        apply_keystream(&key, &mut ciphertext);
+        let tag = tag(&key, &ciphertext);
+        ciphertext.extend_from_slice(&tag);
+        Self {
+            iterations,
+            modulus,
+            val,
+            ciphertext,
+        }
+    }
+
+    /// Opens the puzzle by doing its squarings, and returns the payload.
+    ///
+    /// Returns [`PowError::AuthenticationFailed`] if the puzzle has been modified since it was sealed,
+    /// or [`PowError::InvalidModulus`] if the modulus is even or 1, which no sealed puzzle has.
+    pub fn open(&self) -> Result<Vec<u8>, PowError> {
+        check_modulus(&self.modulus)?;
+        let mut key = integer::reduce(&self.val, &self.modulus);
+        for _ in 0..self.iterations {
+            integer::square_mod(&mut key, &self.modulus);
+        }
+        if self.ciphertext.len() < TAG_LEN {
+            return Err(PowError::AuthenticationFailed);
+        }
+        let (ciphertext, found_tag) = self.ciphertext.split_at(self.ciphertext.len() - TAG_LEN);
+        if tag(&key, ciphertext) != found_tag {
+            return Err(PowError::AuthenticationFailed);
+        }
+        let mut payload = ciphertext.to_vec();
+        apply_keystream(&key, &mut payload);
+        Ok(payload)
+    }
+
+    /// Decodes a puzzle from a string and returns it.
+    ///
+    /// Returns [`PowError::InvalidModulus`] if the modulus is even or 1, since puzzles from untrusted sources could otherwise
+    /// make opening divide by zero.
+    pub fn decode_puzzle(puzzle_string: &str) -> Result<TimeLock, PowError> {
+        let mut decoded_data = decode_parts(puzzle_string, VERSION, 4)?;
+        let modulus = integer::from_bytes(&decoded_data[1]);
+        check_modulus(&modulus)?;
+        Ok(Self {
+            iterations: decode_u64(&decoded_data[0], PowError::DifficultyTooLarge)?,
+            modulus,
+            val: integer::from_bytes(&decoded_data[2]),
+            ciphertext: decoded_data.pop().unwrap(),
+        })
+    }
+}
+
+/// Checks that a modulus could be the product of two odd primes, which rules out the ones that squaring can't be done modulo.
+fn check_modulus(modulus: &Integer) -> Result<(), PowError> {
+    if !integer::is_odd(modulus) || *modulus == Integer::from(1u32) {
+        return Err(PowError::InvalidModulus);
+    }
+    Ok(())
+}
+
+/// Encrypts or decrypts data in place by XORing it with a keystream derived from the key with SHA-256 in counter mode.
+fn apply_keystream(key: &Integer, data: &mut [u8]) {
+    let key = integer::to_bytes(key);
+    for (counter, chunk) in data.chunks_mut(32).enumerate() {
+        let mut hasher = Sha256::new();
+        hasher.update(KEY_DOMAIN);
+        hasher.update((counter as u64).to_be_bytes());
+        hasher.update(&key);
+        for (byte, stream) in chunk.iter_mut().zip(hasher.finalize()) {
+            *byte ^= stream;
+        }
+    }
+}
+
+/// Returns the authentication tag of a ciphertext under a key.
+fn tag(key: &Integer, ciphertext: &[u8]) -> [u8; TAG_LEN] {
+    let key = integer::to_bytes(key);
+    let mut hasher = Sha256::new();
+    hasher.update(TAG_DOMAIN);
+    hasher.update((key.len() as u64).to_be_bytes());
+    hasher.update(&key);
+    hasher.update(ciphertext);
+    hasher.finalize().into()
+}
+
+impl fmt::Display for TimeLock {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            fmt,
+            "{}.{}.{}.{}.{}",
+            VERSION,
+            BASE64_STANDARD.encode(self.iterations.to_be_bytes()),
+            BASE64_STANDARD.encode(integer::to_bytes(&self.modulus)),
+            BASE64_STANDARD.encode(integer::to_bytes(&self.val)),
+            BASE64_STANDARD.encode(&self.ciphertext)
+        )
+    }
+}
+
+impl FromStr for TimeLock {
+    type Err = PowError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::decode_puzzle(s)
+    }
+}
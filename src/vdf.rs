@@ -0,0 +1,100 @@
+//! A verifiable delay function built on the same sloth function as challenges.
+//!
+//! Evaluating takes as long as solving a challenge with a difficulty of `iterations`, while verifying is as fast as checking a solution.
+//! Unlike challenge solutions, each input has exactly one output that verifies, so outputs can be used as unbiased randomness.
+//!
+//! ```rust
+//! use kctf_pow::{vdf, KctfPow};
+//!
+//! let pow = KctfPow::new();
+//! let output = vdf::eval(&pow, b"round 1", 10);
+//! assert!(vdf::verify(&pow, b"round 1", 10, &output));
+//! assert!(!vdf::verify(&pow, b"round 2", 10, &output));
+//! ```
+
+use crate::integer::{self, Integer};
+use crate::{ChallengeParams, KctfPow, Solution, SqrtStrategy};
+use alloc::vec::Vec;
+use sha2::{Digest, Sha256};
+
+/// Separates the hashes done by [`hash_to_field`] from any other use of SHA-256 on the same seed.
+const HASH_DOMAIN: &[u8] = b"kctf-pow vdf hash to field";
+
+/// The output of the verifiable delay function.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Output {
+    /// The value of the output, which is always below the modulus.
+    pub val: Integer,
+}
+
+impl Output {
+    /// Creates an output from its value as a big-endian integer.
+    pub fn from_bytes(bytes: &[u8]) -> Output {
+        Self {
+            val: integer::from_bytes(bytes),
+        }
+    }
+
+    /// Returns the value of the output as a big-endian integer.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        integer::to_bytes(&self.val)
+    }
+}
+
+/// Maps a seed of any length to a value below the modulus of a proof-of-work system.
+///
+/// The seed is expanded with SHA-256 in counter mode, and the result is reduced by the modulus.
+pub fn hash_to_field(pow: &KctfPow, seed: &[u8]) -> Integer {
+    let len = integer::uniform_len(&pow.modulus);
+    let mut bytes = Vec::with_capacity(len);
+    let mut counter = 0u32;
+    while bytes.len() < len {
+        let mut hasher = Sha256::new();
+        hasher.update(HASH_DOMAIN);
+        hasher.update(counter.to_be_bytes());
+        hasher.update(seed);
+        bytes.extend_from_slice(&hasher.finalize());
+        counter += 1;
+    }
+    bytes.truncate(len);
+    integer::reduce(&integer::from_bytes(&bytes), &pow.modulus)
+}
+
+/// Evaluates the verifiable delay function on an input with a given number of iterations.
+pub fn eval(pow: &KctfPow, input: &[u8], iterations: u32) -> Output {
+    let sol = params(pow, input, iterations).solve(pow);
+    Output {
+        val: canonical(pow, sol.val),
+    }
+}
+
+/// Verifies that an output is the result of evaluating the verifiable delay function on an input with a given number of iterations.
+pub fn verify(pow: &KctfPow, input: &[u8], iterations: u32, output: &Output) -> bool {
+    output.val < pow.modulus
+        && canonical(pow, output.val.clone()) == output.val
+        && Solution {
+            val: output.val.clone(),
+        }
+        .check(&params(pow, input, iterations), pow)
+}
+
+/// Returns the challenge whose solution is the output for an input.
+fn params(pow: &KctfPow, input: &[u8], iterations: u32) -> ChallengeParams {
+    ChallengeParams {
+        difficulty: iterations,
+        val: hash_to_field(pow, input),
+    }
+}
+
+/// Picks one of the solutions that checking accepts, so that every input has a unique output.
+fn canonical(pow: &KctfPow, val: Integer) -> Integer {
+    match pow.sqrt {
+        // a solution and its negation are both accepted, so pick the smaller one
+        SqrtStrategy::Exponent => match integer::negate_mod(&val, &pow.modulus) {
+            Some(neg) if neg < val => neg,
+            _ => val,
+        },
+        // the solution is already unique
+        SqrtStrategy::TonelliShanks { .. } => val,
+    }
+}
@@ -0,0 +1,76 @@
+//! Checking many solutions without allocating for each one.
+
+use crate::mersenne::Mersenne1279;
+use crate::{ChallengeParams, KctfPow, PowError, Solution, VERSION};
+use alloc::vec::Vec;
+use base64::prelude::*;
+
+/// A checker that reuses its buffers between solutions, for servers that check many solutions.
+///
+/// With kCTF's modulus, checking a solution doesn't allocate once the decode buffer has grown to fit the longest solution seen,
+/// apart from errors and solutions too large to be valid. With other moduli, this falls back to [`Solution::check`].
+///
+/// ```rust
+/// use kctf_pow::{KctfPow, Verifier};
+///
+/// let pow = KctfPow::new();
+/// let mut verifier = Verifier::new(&pow);
+/// let chall = pow.decode_challenge("s.AAAAMg==.NDtqORW1uZlIgzszbdMGZA==").unwrap();
+/// let sol = "s.NUH3arymnKB+ysUGdv+67ypDamn4wOKCPORB2ivWE1Yhinam2v4S6q4nAoC5LP97LScdVoq+NuFVF++Win5mNRYZS6bJAs8fk0h8XgvfcC/7JfmFISqeCIo/CIUgIucVAM+eGDjqitRULGXqIOyviJoJjW8DMouMRuJM/3eg/z18kutQHkX0N3sqPeF7Nzkk8S3Bs6aiHUORM30syUKYug==";
+/// assert_eq!(verifier.check(&chall.params, sol), Ok(true));
+/// assert_eq!(verifier.check(&chall.params, "s.asdf"), Ok(false));
+/// ```
+#[derive(Debug, Clone)]
+pub struct Verifier<'a> {
+    pow: &'a KctfPow,
+    /// Whether the proof-of-work system can use the fixed-width kernel.
+    fast: bool,
+    /// The decoded bytes of the last solution.
+    bytes: Vec<u8>,
+}
+
+impl<'a> Verifier<'a> {
+    /// Creates a new verifier for a proof-of-work system.
+    pub fn new(pow: &'a KctfPow) -> Self {
+        Self {
+            pow,
+            fast: Mersenne1279::matches(pow),
+            bytes: Vec::new(),
+        }
+    }
+
+    /// Checks a solution to see if it satisfies a challenge.
+    ///
+    /// This gives the same results as [`ChallengeParams::check`].
+    pub fn check(&mut self, params: &ChallengeParams, sol: &str) -> Result<bool, PowError> {
+        let mut parts = sol.split('.');
+        match parts.next() {
+            Some(VERSION) => {}
+            found => return Err(PowError::WrongVersion(found.unwrap_or_default().into())),
+        }
+        let encoded = match (parts.next(), parts.next()) {
+            (Some(encoded), None) => encoded,
+            _ => return Err(PowError::WrongPartCount),
+        };
+        self.bytes.clear();
+        BASE64_STANDARD
+            .decode_vec(encoded, &mut self.bytes)
+            .map_err(|_| PowError::InvalidBase64 { part: 1 })?;
+        let fast_vals = if self.fast {
+            Mersenne1279::from_be_bytes(&self.bytes)
+                .zip(Mersenne1279::from_integer_unreduced(&params.val))
+        } else {
+            None
+        };
+        let (mut val, start) = match fast_vals {
+            Some(vals) => vals,
+            None => return Ok(Solution::from_bytes(&self.bytes).check(params, self.pow)),
+        };
+        // the values are loaded without reducing them, so this matches the generic path even for unreduced solutions
+        for _ in 0..params.difficulty {
+            val.flip_low_bit();
+            val.square();
+        }
+        Ok(val == start || start.negate_mod() == Some(val))
+    }
+}
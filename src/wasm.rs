@@ -0,0 +1,44 @@
+//! Bindings for solving and checking challenges from JavaScript, built with [`wasm-bindgen`](https://docs.rs/wasm-bindgen).
+//!
+//! These always use kCTF's modulus, and give the same solutions as [`ChallengeParams::solve`].
+//! Errors are thrown as JavaScript `Error`s with the message of the [`PowError`](crate::PowError) or [`Cancelled`](crate::Cancelled).
+
+use crate::{ChallengeParams, KctfPow};
+use alloc::string::{String, ToString};
+use core::ops::ControlFlow;
+use js_sys::Function;
+use wasm_bindgen::prelude::*;
+
+/// Solves a challenge and returns the solution.
+#[wasm_bindgen]
+pub fn solve(challenge: &str) -> Result<String, JsError> {
+    let params = ChallengeParams::decode_challenge(challenge)?;
+    Ok(params.solve(&KctfPow::new()).to_string())
+}
+
+/// Solves a challenge while reporting progress and returns the solution.
+///
+/// `progress` is called with the number of iterations done and the total number of iterations,
+/// as described in [`ChallengeParams::solve_with`]. If it returns `false`, the solve is stopped and an error is thrown.
+/// Any other return value, including `undefined`, continues the solve.
+#[wasm_bindgen(js_name = solveWith)]
+pub fn solve_with(challenge: &str, interval: u32, progress: &Function) -> Result<String, JsError> {
+    let params = ChallengeParams::decode_challenge(challenge)?;
+    let sol = params.solve_with(&KctfPow::new(), interval, |done, total| {
+        // an exception thrown by the callback stops the solve too, but its value is replaced by `Cancelled`
+        match progress.call2(&JsValue::NULL, &done.into(), &total.into()) {
+            Ok(ret) if ret != JsValue::FALSE => ControlFlow::Continue(()),
+            _ => ControlFlow::Break(()),
+        }
+    })?;
+    Ok(sol.to_string())
+}
+
+/// Checks a solution to see if it satisfies a challenge.
+///
+/// Like [`ChallengeParams::check`], this throws if the solution can't be decoded.
+#[wasm_bindgen]
+pub fn check(challenge: &str, solution: &str) -> Result<bool, JsError> {
+    let params = ChallengeParams::decode_challenge(challenge)?;
+    Ok(params.check(&KctfPow::new(), solution)?)
+}
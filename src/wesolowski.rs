@@ -0,0 +1,324 @@
+//! A repeated-squaring verifiable delay function with Wesolowski proofs, for delays too long to check by redoing them.
+//!
+//! Solving a challenge with `iterations` iterations takes `2 * iterations` modular squarings, but checking the solution
+//! only takes a few modular exponentiations no matter how many iterations there are.
+//! Squaring is done modulo an RSA modulus whose factors nobody knows, since anyone who knows them can skip the delay.
+
+use crate::integer::{self, Integer};
+use crate::{decode_parts, decode_u64, prime, PowError};
+use base64::prelude::*;
+use core::convert::TryInto;
+use core::fmt;
+use core::str::FromStr;
+use rand::prelude::*;
+use sha2::{Digest, Sha256};
+
+const VERSION: &str = "w";
+/// Separates the hashes done by [`hash_to_prime`] from any other use of SHA-256 on the same values.
+const HASH_DOMAIN: &[u8] = b"kctf-pow wesolowski hash to prime";
+
+/// A repeated-squaring verifiable delay function with Wesolowski proofs.
+///
+/// Values are only considered up to their sign modulo the modulus, since `-1` has a known order and would otherwise allow forged proofs.
+///
+/// ```rust
+/// use kctf_pow::Wesolowski;
+/// use rand::SeedableRng;
+/// use rand_chacha::ChaCha20Rng;
+///
+/// let mut rng = ChaCha20Rng::seed_from_u64(0);
+/// // real moduli should be at least 2048 bits, and generated by someone trusted to forget the factors
+/// let vdf = Wesolowski::generate(&mut rng, 512);
+/// let chall = vdf.generate_challenge_with_rng(&mut rng, 1000);
+/// let sol = chall.clone().solve();
+/// assert!(chall.check_solution(&sol));
+/// // checking a solution string doesn't redo the squarings
+/// let chall = vdf.decode_challenge(&chall.to_string()).unwrap();
+/// assert_eq!(chall.check(&sol.to_string()), Ok(true));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Wesolowski {
+    /// The RSA modulus that squaring is done modulo.
+    pub modulus: Integer,
+}
+
+/// The parameters for a delay challenge.
+///
+/// If you want to serialize it to a string, use the [`Display`](core::fmt::Display) implementation.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct DelayParams {
+    /// The number of squarings that the solution has to do.
+    pub iterations: u64,
+    /// The starting value.
+    pub val: Integer,
+}
+
+/// The solution to a delay challenge, along with a proof that it's correct.
+///
+/// If you want to serialize it to a string, use the [`Display`](core::fmt::Display) implementation.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct DelaySolution {
+    /// The starting value squared `iterations` times.
+    pub output: Integer,
+    /// The Wesolowski proof that the output is correct.
+    pub proof: Integer,
+}
+
+/// A delay challenge.
+///
+/// Contains a reference to the [`Wesolowski`] that created the challenge. If you want to serialize it to a string, use the [`Display`](core::fmt::Display) implementation.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct DelayChallenge<'a> {
+    /// The parameters of the challenge.
+    pub params: DelayParams,
+    /// The verifiable delay function that the challenge is for.
+    pub vdf: &'a Wesolowski,
+}
+
+impl Wesolowski {
+    /// Creates a new instance with a given RSA modulus.
+    ///
+    /// The factors of the modulus must be unknown to anyone solving challenges, for example by using the
+    /// RSA-2048 challenge number or a modulus from a multi-party setup. Returns [`PowError::InvalidModulus`]
+    /// if the modulus isn't odd and composite, but can't check whether its factors are known.
+    pub fn with_modulus(modulus: Integer) -> Result<Self, PowError> {
+        if !integer::is_odd(&modulus)
+            || modulus <= Integer::from(1u32)
+            || prime::is_probable_prime(&modulus)
+        {
+            return Err(PowError::InvalidModulus);
+        }
+        Ok(Self { modulus })
+    }
+
+    /// Creates a new instance with a random RSA modulus of `bits` bits, which should be at least 2048.
+    ///
+    /// The factors of the modulus are dropped once it's created, but they're still in memory while this runs,
+    /// so this should only be done by a party trusted not to keep them.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `bits` is less than 10, since smaller moduli can't be the product of two different primes of about the same size.
+    pub fn generate<R: CryptoRng + RngCore>(rng: &mut R, bits: u32) -> Self {
+        assert!(
+            bits >= prime::MIN_PAIR_BITS,
+            "modulus must have at least 10 bits"
+        );
+        let (p, q) = prime::random_prime_pair(rng, bits);
+        Self { modulus: p * q }
+    }
+
+    /// Decodes a challenge from a string and returns it.
+    pub fn decode_challenge(&self, chall_string: &str) -> Result<DelayChallenge<'_>, PowError> {
+        Ok(DelayChallenge {
+            params: DelayParams::decode_challenge(chall_string)?,
+            vdf: self,
+        })
+    }
+
+    /// Generates a random challenge given a number of iterations.
+    #[cfg(feature = "std")]
+    pub fn generate_challenge(&self, iterations: u64) -> DelayChallenge<'_> {
+        self.generate_challenge_with_rng(&mut thread_rng(), iterations)
+    }
+
+    /// Generates a random challenge given a number of iterations, using a specific random number generator.
+    pub fn generate_challenge_with_rng<R: CryptoRng + RngCore>(
+        &self,
+        rng: &mut R,
+        iterations: u64,
+    ) -> DelayChallenge<'_> {
+        DelayChallenge {
+            params: DelayParams::generate_challenge_with_rng(self, rng, iterations),
+            vdf: self,
+        }
+    }
+
+    /// Returns whichever of a reduced value and its negation is smaller.
+    fn canonical(&self, val: Integer) -> Integer {
+        match integer::negate_mod(&val, &self.modulus) {
+            Some(neg) if neg < val => neg,
+            _ => val,
+        }
+    }
+
+    /// Returns whether a value is nonzero, reduced, and the smaller of itself and its negation.
+    fn is_canonical(&self, val: &Integer) -> bool {
+        *val != Integer::from(0u32) && *val < self.modulus && self.canonical(val.clone()) == *val
+    }
+}
+
+impl DelayParams {
+    /// Decodes a challenge from a string and returns it.
+    pub fn decode_challenge(chall_string: &str) -> Result<DelayParams, PowError> {
+        let decoded_data = decode_parts(chall_string, VERSION, 2)?;
+        Ok(Self {
+            val: integer::from_bytes(&decoded_data[1]),
+            iterations: decode_u64(&decoded_data[0], PowError::DifficultyTooLarge)?,
+        })
+    }
+
+    /// Generates a random challenge given a verifiable delay function and a number of iterations, using a specific random number generator.
+    pub fn generate_challenge_with_rng<R: CryptoRng + RngCore>(
+        vdf: &Wesolowski,
+        rng: &mut R,
+        iterations: u64,
+    ) -> DelayParams {
+        loop {
+            let val = integer::random_below(rng, &vdf.modulus);
+            if val != Integer::from(0u32) {
+                return Self { iterations, val };
+            }
+        }
+    }
+
+    /// Solves a challenge given a verifiable delay function and returns the solution with its proof.
+    pub fn solve(self, vdf: &Wesolowski) -> DelaySolution {
+        let modulus = &vdf.modulus;
+        let start = integer::reduce(&self.val, modulus);
+        let mut output = start.clone();
+        for _ in 0..self.iterations {
+            integer::square_mod(&mut output, modulus);
+        }
+        let output = vdf.canonical(output);
+        // the proof is start**(2**iterations / l), where the bits of the quotient are found by long division
+        let l = hash_to_prime(vdf, &self, &output);
+        let mut proof = Integer::from(1u32);
+        let mut rem = 1u128;
+        for _ in 0..self.iterations {
+            integer::square_mod(&mut proof, modulus);
+            // l is below 2**127, so this can't overflow
+            rem *= 2;
+            if rem >= l {
+                rem -= l;
+                proof = integer::mul_mod(&proof, &start, modulus);
+            }
+        }
+        DelaySolution {
+            output,
+            proof: vdf.canonical(proof),
+        }
+    }
+
+    /// Checks a solution to see if it satisfies the challenge under a given verifiable delay function.
+    pub fn check(&self, vdf: &Wesolowski, sol: &str) -> Result<bool, PowError> {
+        Ok(sol.parse::<DelaySolution>()?.check(self, vdf))
+    }
+}
+
+impl DelaySolution {
+    /// Decodes a solution from a string and returns it.
+    pub fn decode_solution(sol_string: &str) -> Result<DelaySolution, PowError> {
+        let decoded_data = decode_parts(sol_string, VERSION, 2)?;
+        Ok(Self {
+            output: integer::from_bytes(&decoded_data[0]),
+            proof: integer::from_bytes(&decoded_data[1]),
+        })
+    }
+
+    /// Checks the solution and its proof to see if it satisfies a challenge under a given verifiable delay function.
+    pub fn check(&self, params: &DelayParams, vdf: &Wesolowski) -> bool {
+        let modulus = &vdf.modulus;
+        let start = integer::reduce(&params.val, modulus);
+        if start == Integer::from(0u32)
+            || !vdf.is_canonical(&self.output)
+            || !vdf.is_canonical(&self.proof)
+        {
+            return false;
+        }
+        // proof**l * start**(2**iterations mod l) is start**(2**iterations) when the proof is correct
+        let l = Integer::from(hash_to_prime(vdf, params, &self.output));
+        let mut rem = Integer::from(2u32);
+        integer::pow_mod(&mut rem, &Integer::from(params.iterations), &l);
+        let mut proof_pow = self.proof.clone();
+        integer::pow_mod(&mut proof_pow, &l, modulus);
+        let mut start_pow = start;
+        integer::pow_mod(&mut start_pow, &rem, modulus);
+        vdf.canonical(integer::mul_mod(&proof_pow, &start_pow, modulus)) == self.output
+    }
+}
+
+impl<'a> DelayChallenge<'a> {
+    /// Solves a challenge and returns the solution with its proof.
+    pub fn solve(self) -> DelaySolution {
+        self.params.solve(self.vdf)
+    }
+
+    /// Checks a solution to see if it satisfies the challenge.
+    pub fn check(&self, sol: &str) -> Result<bool, PowError> {
+        self.params.check(self.vdf, sol)
+    }
+
+    /// Checks an already decoded solution to see if it satisfies the challenge.
+    pub fn check_solution(&self, sol: &DelaySolution) -> bool {
+        sol.check(&self.params, self.vdf)
+    }
+}
+
+/// Hashes a challenge and its output to a 127-bit prime, which is the challenge for the proof.
+fn hash_to_prime(vdf: &Wesolowski, params: &DelayParams, output: &Integer) -> u128 {
+    let mut counter = 0u32;
+    loop {
+        let mut hasher = Sha256::new();
+        hasher.update(HASH_DOMAIN);
+        hasher.update(counter.to_be_bytes());
+        hasher.update(params.iterations.to_be_bytes());
+        for val in [&vdf.modulus, &params.val, output] {
+            let bytes = integer::to_bytes(val);
+            hasher.update((bytes.len() as u64).to_be_bytes());
+            hasher.update(bytes);
+        }
+        let hash = hasher.finalize();
+        let candidate = u128::from_be_bytes(hash[..16].try_into().unwrap()) >> 1 | (1 << 126) | 1;
+        if prime::is_probable_prime(&Integer::from(candidate)) {
+            return candidate;
+        }
+        counter += 1;
+    }
+}
+
+impl fmt::Display for DelayParams {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            fmt,
+            "{}.{}.{}",
+            VERSION,
+            BASE64_STANDARD.encode(self.iterations.to_be_bytes()),
+            BASE64_STANDARD.encode(integer::to_bytes(&self.val))
+        )
+    }
+}
+
+impl fmt::Display for DelaySolution {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            fmt,
+            "{}.{}.{}",
+            VERSION,
+            BASE64_STANDARD.encode(integer::to_bytes(&self.output)),
+            BASE64_STANDARD.encode(integer::to_bytes(&self.proof))
+        )
+    }
+}
+
+impl<'a> fmt::Display for DelayChallenge<'a> {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(fmt, "{}", self.params)
+    }
+}
+
+impl FromStr for DelayParams {
+    type Err = PowError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::decode_challenge(s)
+    }
+}
+
+impl FromStr for DelaySolution {
+    type Err = PowError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::decode_solution(s)
+    }
+}
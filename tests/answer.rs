@@ -0,0 +1,103 @@
+//! Challenges generated along with their answer, so that solutions can be checked without undoing the solve.
+
+use kctf_pow::{Integer, KctfPow, Solution, SqrtStrategy};
+use rand::SeedableRng;
+use rand_chacha::ChaCha20Rng;
+
+/// Systems using each square root strategy, with moduli small enough that solving is quick.
+fn systems() -> Vec<KctfPow> {
+    let curve25519 = KctfPow::with_modulus((Integer::from(1u32) << 255u32) - 19u32).unwrap();
+    assert!(matches!(
+        curve25519.sqrt,
+        SqrtStrategy::TonelliShanks { .. }
+    ));
+    vec![KctfPow::new(), KctfPow::mersenne_521(), curve25519]
+}
+
+#[test]
+fn answer_checks() {
+    let mut rng = ChaCha20Rng::seed_from_u64(13);
+    for pow in systems() {
+        for difficulty in [1, 2, 50] {
+            let (chall, answer) = pow.generate_with_answer_with_rng(&mut rng, difficulty);
+            assert!(chall.check_solution(&answer), "checking {}", chall);
+            assert!(answer.check_against_answer(&answer, &pow));
+            assert_eq!(
+                chall.check_against_answer(&answer.to_string(), &answer),
+                Ok(true)
+            );
+        }
+    }
+}
+
+#[test]
+fn solving_gives_answer_up_to_sign() {
+    let mut rng = ChaCha20Rng::seed_from_u64(13);
+    for pow in systems() {
+        for difficulty in [1, 2, 50] {
+            let (chall, answer) = pow.generate_with_answer_with_rng(&mut rng, difficulty);
+            let sol = chall.clone().solve();
+            assert!(chall.check_solution(&sol), "solving {}", chall);
+            assert_eq!(
+                chall.check_against_answer(&sol.to_string(), &answer),
+                Ok(true),
+                "solving {}",
+                chall
+            );
+            match pow.sqrt {
+                // the answer loses its sign when it's squared, so solving may find its negation instead
+                SqrtStrategy::Exponent => assert!(
+                    sol.val == answer.val || sol.val == pow.modulus.clone() - answer.val.clone(),
+                    "solving {}",
+                    chall
+                ),
+                // every step is a bijection, so solving finds the answer itself
+                SqrtStrategy::TonelliShanks { .. } => {
+                    assert_eq!(sol, answer, "solving {}", chall)
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn unreduced_solutions_check() {
+    let mut rng = ChaCha20Rng::seed_from_u64(13);
+    for pow in systems() {
+        for _ in 0..8 {
+            let (chall, answer) = pow.generate_with_answer_with_rng(&mut rng, 10);
+            let unreduced = match pow.sqrt {
+                // the low bit is flipped before reducing, so adding the modulus has to be corrected by 2 to square the same value
+                SqrtStrategy::Exponent if answer.val.clone() % 2u32 == Integer::from(0u32) => {
+                    answer.val.clone() + pow.modulus.clone() + 2u32
+                }
+                SqrtStrategy::Exponent => answer.val.clone() + pow.modulus.clone() - 2u32,
+                SqrtStrategy::TonelliShanks { .. } => answer.val.clone() + pow.modulus.clone(),
+            };
+            let sol = Solution { val: unreduced };
+            assert!(chall.check_solution(&sol), "checking {}", sol);
+            assert!(sol.check_against_answer(&answer, &pow), "checking {}", sol);
+            assert_eq!(
+                chall.check_against_answer(&sol.to_string(), &answer),
+                Ok(true)
+            );
+        }
+    }
+}
+
+#[test]
+fn wrong_solutions_fail() {
+    let mut rng = ChaCha20Rng::seed_from_u64(13);
+    for pow in systems() {
+        let (chall, answer) = pow.generate_with_answer_with_rng(&mut rng, 10);
+        let (_, other_answer) = pow.generate_with_answer_with_rng(&mut rng, 10);
+        let mut wrong = answer.clone();
+        wrong.val = (wrong.val + 1u32) % pow.modulus.clone();
+        for sol in [wrong, other_answer] {
+            assert!(!sol.check_against_answer(&answer, &pow), "checking {}", sol);
+            assert!(!chall.check_solution(&sol), "checking {}", sol);
+        }
+        assert_eq!(chall.check_against_answer("s.", &answer), Ok(false));
+        assert!(chall.check_against_answer("x.AQ==", &answer).is_err());
+    }
+}
@@ -0,0 +1,82 @@
+//! Solving on tokio blocking threads.
+
+#![cfg(feature = "tokio")]
+
+use kctf_pow::{Cancelled, ChallengeParams, KctfPow, SolveHandle};
+use std::sync::mpsc;
+use std::thread;
+use std::time::{Duration, Instant};
+use tokio::runtime::{Builder, Runtime};
+
+const CHALLENGE: &str = "s.AAAAMg==.NDtqORW1uZlIgzszbdMGZA==";
+
+#[test]
+fn solves() {
+    let pow = KctfPow::new();
+    let chall = pow.decode_challenge(CHALLENGE).unwrap();
+    let runtime = Builder::new_current_thread().build().unwrap();
+    let sol = runtime
+        .block_on(chall.clone().solve_async())
+        .expect("solve shouldn't be cancelled");
+    assert!(chall.check_solution(&sol));
+}
+
+#[test]
+fn runtime_shutdown_cancels() {
+    let pow = KctfPow::new();
+    let runtime = Builder::new_current_thread().build().unwrap();
+    let handle = runtime.handle().clone();
+    runtime.shutdown_background();
+    // the runtime is already shut down, so the solve never gets to start
+    let solve = {
+        let _guard = handle.enter();
+        pow.decode_challenge(CHALLENGE)
+            .unwrap()
+            .params
+            .spawn_solve(&pow)
+    };
+    let other = Builder::new_current_thread().build().unwrap();
+    assert_eq!(other.block_on(solve), Err(Cancelled));
+}
+
+/// Starts a solve that would take far longer than the test, and waits until it has done a few iterations.
+fn spawn_slow(runtime: &Runtime) -> SolveHandle {
+    let pow = KctfPow::new();
+    let params = ChallengeParams {
+        difficulty: u32::MAX,
+        val: 1u32.into(),
+    };
+    let handle = {
+        let _guard = runtime.enter();
+        params.spawn_solve(&pow)
+    };
+    let deadline = Instant::now() + Duration::from_secs(60);
+    while handle.progress().0 < 10 {
+        assert!(Instant::now() < deadline, "solve didn't start");
+        thread::sleep(Duration::from_millis(1));
+    }
+    handle
+}
+
+#[test]
+fn cancel_stops_solve() {
+    let runtime = Builder::new_current_thread().build().unwrap();
+    let handle = spawn_slow(&runtime);
+    handle.cancel();
+    let (done, total) = handle.progress();
+    assert!(done < total);
+    assert_eq!(runtime.block_on(handle), Err(Cancelled));
+}
+
+#[test]
+fn drop_stops_solve() {
+    let runtime = Builder::new_current_thread().build().unwrap();
+    drop(spawn_slow(&runtime));
+    // dropping a runtime waits for its blocking threads, so this only finishes if the solve stopped
+    let (sender, receiver) = mpsc::channel();
+    thread::spawn(move || {
+        drop(runtime);
+        sender.send(()).unwrap();
+    });
+    assert!(receiver.recv_timeout(Duration::from_secs(60)).is_ok());
+}
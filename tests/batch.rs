@@ -0,0 +1,134 @@
+//! Batch checking and [`Verifier`], which must give exactly the same results as checking one solution at a time.
+//!
+//! Run them both serially and in parallel with `cargo test --test batch` and `cargo test --test batch --features rayon`.
+
+use kctf_pow::{ChallengeParams, Integer, KctfPow, Solution, Verifier};
+use rand::SeedableRng;
+use rand_chacha::ChaCha20Rng;
+
+/// Systems that use kCTF's fixed-width kernel, the generic path, and Tonelli-Shanks.
+fn systems() -> Vec<KctfPow> {
+    vec![
+        KctfPow::new(),
+        KctfPow::mersenne_521(),
+        KctfPow::with_modulus((Integer::from(1u32) << 255u32) - 19u32).unwrap(),
+    ]
+}
+
+/// Returns challenges, including ones with unreduced starting values, and solutions that are correct, incorrect, malformed, and too large.
+fn cases(pow: &KctfPow) -> (Vec<ChallengeParams>, Vec<String>) {
+    let mut rng = ChaCha20Rng::seed_from_u64(19);
+    let mut challs = Vec::new();
+    let mut sols = Vec::new();
+    for difficulty in [0, 1, 7] {
+        let (chall, answer) = pow.generate_with_answer_with_rng(&mut rng, difficulty);
+        let negated = pow.modulus.clone() - answer.val.clone();
+        for val in [
+            answer.val.clone(),
+            negated,
+            answer.val.clone() + 1u32,
+            answer.val.clone() + pow.modulus.clone(),
+            answer.val.clone() + (Integer::from(1u32) << 1400u32),
+        ] {
+            sols.push(Solution { val }.to_string());
+        }
+        challs.push(ChallengeParams {
+            difficulty,
+            val: chall.params.val.clone() + pow.modulus.clone(),
+        });
+        challs.push(chall.params);
+    }
+    challs.push("s.AAAAMg==.NDtqORW1uZlIgzszbdMGZA==".parse().unwrap());
+    sols.push("s.NUH3arymnKB+ysUGdv+67ypDamn4wOKCPORB2ivWE1Yhinam2v4S6q4nAoC5LP97LScdVoq+NuFVF++Win5mNRYZS6bJAs8fk0h8XgvfcC/7JfmFISqeCIo/CIUgIucVAM+eGDjqitRULGXqIOyviJoJjW8DMouMRuJM/3eg/z18kutQHkX0N3sqPeF7Nzkk8S3Bs6aiHUORM30syUKYug==".into());
+    for sol in [
+        "s.",
+        "s.AA==",
+        "s.asdf",
+        "",
+        "s",
+        "x.AQ==",
+        "s.AQ==.AQ==",
+        "s.A*==",
+        "s.AQ",
+        "s.AR==",
+    ] {
+        sols.push(sol.into());
+    }
+    (challs, sols)
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn solve_many_keeps_order() {
+    for pow in systems() {
+        let mut rng = ChaCha20Rng::seed_from_u64(20);
+        // different difficulties make the challenges finish out of order
+        let challs: Vec<_> = [30, 1, 20, 0, 5, 10, 2]
+            .iter()
+            .map(|&difficulty| ChallengeParams::generate_challenge_with_rng(&mut rng, difficulty))
+            .collect();
+        let expected: Vec<_> = challs
+            .iter()
+            .map(|chall| chall.clone().solve(&pow))
+            .collect();
+        for threads in [0, 1, 3, challs.len() + 5] {
+            assert_eq!(
+                pow.solve_many(&challs, threads),
+                expected,
+                "solving on {} threads",
+                threads
+            );
+        }
+        for threads in [0, 1, 3] {
+            assert!(pow.solve_many(&[], threads).is_empty());
+        }
+    }
+}
+
+#[test]
+fn check_batch_matches_serial() {
+    for pow in systems() {
+        let (challs, sols) = cases(&pow);
+        let checks: Vec<(ChallengeParams, &str)> = challs
+            .iter()
+            .flat_map(|chall| sols.iter().map(move |sol| (chall.clone(), sol.as_str())))
+            .collect();
+        let expected: Vec<_> = checks
+            .iter()
+            .map(|(chall, sol)| chall.check(&pow, sol))
+            .collect();
+        // make sure every kind of result is covered
+        assert!(expected.contains(&Ok(true)));
+        assert!(expected.contains(&Ok(false)));
+        assert!(expected.iter().any(Result::is_err));
+        assert_eq!(pow.check_batch(&checks), expected);
+        #[cfg(feature = "rayon")]
+        {
+            let pool = rayon::ThreadPoolBuilder::new()
+                .num_threads(3)
+                .build()
+                .unwrap();
+            assert_eq!(pow.check_batch_in(&pool, &checks), expected);
+        }
+    }
+}
+
+#[test]
+fn verifier_matches_serial() {
+    for pow in systems() {
+        let (challs, sols) = cases(&pow);
+        // one verifier for everything, so that its buffer is reused between solutions of different lengths
+        let mut verifier = Verifier::new(&pow);
+        for chall in &challs {
+            for sol in &sols {
+                assert_eq!(
+                    verifier.check(chall, sol),
+                    chall.check(&pow, sol),
+                    "checking {} against {}",
+                    sol,
+                    chall
+                );
+            }
+        }
+    }
+}
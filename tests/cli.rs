@@ -0,0 +1,76 @@
+//! Generating and solving challenges with the command-line interface.
+
+#![cfg(feature = "std")]
+
+use kctf_pow::{HashcashParams, HashcashSolution, KctfPow};
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+fn gen(args: &[&str]) -> String {
+    let output = Command::new(env!("CARGO_BIN_EXE_kctf-pow"))
+        .arg("gen")
+        .args(args)
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "generating with {:?}", args);
+    String::from_utf8(output.stdout).unwrap()
+}
+
+#[test]
+fn seed_gives_fixed_challenge() {
+    // fixtures depend on this staying the same across versions and big integer backends
+    assert_eq!(
+        gen(&["--seed", "fixture", "50"]),
+        "s.AAAAMg==.DZLgUpnUdPJex/5NS0K/Gw==\n"
+    );
+}
+
+#[test]
+fn seeds_dont_collide() {
+    let seeds = ["", "0", "00", "01", "0100", "fixture"];
+    let mut challs: Vec<_> = seeds
+        .iter()
+        .map(|seed| gen(&["--seed", seed, "50"]))
+        .collect();
+    challs.sort();
+    challs.dedup();
+    assert_eq!(challs.len(), seeds.len());
+}
+
+#[test]
+fn solves_stdin_in_order() {
+    let challs = [
+        "s.AAAAMg==.NDtqORW1uZlIgzszbdMGZA==",
+        "h.AAAACA==.AQ==",
+        "s.AAAAAQ==.AQ==",
+        "h.AAAAAA==.Ag==",
+        "s.AAAAAA==.Aw==",
+    ];
+    for threads in ["0", "1", "2"] {
+        let mut child = Command::new(env!("CARGO_BIN_EXE_kctf-pow"))
+            .args(["solve", "--threads", threads])
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .spawn()
+            .unwrap();
+        let mut stdin = child.stdin.take().unwrap();
+        // blank lines are skipped
+        write!(stdin, "{}\n\n", challs.join("\n")).unwrap();
+        drop(stdin);
+        let output = child.wait_with_output().unwrap();
+        assert!(output.status.success(), "solving on {} threads", threads);
+        let output = String::from_utf8(output.stdout).unwrap();
+        let sols: Vec<_> = output.lines().collect();
+        assert_eq!(sols.len(), challs.len());
+        let pow = KctfPow::new();
+        for (chall, sol) in challs.iter().zip(sols) {
+            let correct = if chall.starts_with("h.") {
+                let sol: HashcashSolution = sol.parse().unwrap();
+                sol.check(&chall.parse::<HashcashParams>().unwrap())
+            } else {
+                pow.decode_challenge(chall).unwrap().check(sol).unwrap()
+            };
+            assert!(correct, "solving {} on {} threads", chall, threads);
+        }
+    }
+}
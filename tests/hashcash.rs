@@ -0,0 +1,59 @@
+//! Hashcash challenges, whose difficulty is bounded by the length of a SHA-256 hash.
+
+use kctf_pow::{Hashcash, HashcashParams, HashcashSolution, PowError, PowScheme};
+use rand::SeedableRng;
+use rand_chacha::ChaCha20Rng;
+
+#[test]
+fn round_trips() {
+    let mut rng = ChaCha20Rng::seed_from_u64(16);
+    for difficulty in [0, 1, 8, 12] {
+        let chall = Hashcash.generate_with_rng(&mut rng, difficulty).unwrap();
+        let chall: HashcashParams = chall.to_string().parse().unwrap();
+        let sol = chall.solve();
+        assert!(sol.check(&chall), "solving {}", chall);
+        let decoded: HashcashSolution = sol.to_string().parse().unwrap();
+        assert_eq!(decoded, sol);
+        // the smallest counter is always found, so the previous one doesn't work
+        if let Some(counter) = sol.counter.checked_sub(1) {
+            assert!(!HashcashSolution { counter }.check(&chall));
+        }
+        #[cfg(feature = "std")]
+        for threads in [0, 1, 3] {
+            assert_eq!(chall.solve_parallel(threads), sol, "solving {}", chall);
+        }
+    }
+}
+
+#[test]
+fn max_difficulty() {
+    let mut rng = ChaCha20Rng::seed_from_u64(16);
+    assert!(
+        HashcashParams::generate_challenge_with_rng(&mut rng, Hashcash::MAX_DIFFICULTY).is_ok()
+    );
+    for difficulty in [Hashcash::MAX_DIFFICULTY + 1, u32::MAX] {
+        assert_eq!(
+            HashcashParams::generate_challenge_with_rng(&mut rng, difficulty),
+            Err(PowError::DifficultyTooLarge)
+        );
+    }
+    // 256 and 257 leading zero bits
+    assert!("h.AAABAA==.AQ==".parse::<HashcashParams>().is_ok());
+    assert_eq!(
+        "h.AAABAQ==.AQ==".parse::<HashcashParams>(),
+        Err(PowError::DifficultyTooLarge)
+    );
+}
+
+#[test]
+fn counter_overflow() {
+    // a 9-byte counter only fits if its first byte is 0
+    assert_eq!(
+        "h.AP//////////".parse::<HashcashSolution>(),
+        Ok(HashcashSolution { counter: u64::MAX })
+    );
+    assert_eq!(
+        "h.AQAAAAAAAAAA".parse::<HashcashSolution>(),
+        Err(PowError::ValueOutOfRange)
+    );
+}
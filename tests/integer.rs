@@ -0,0 +1,56 @@
+//! The crate's own integer type, which has to behave the same with either backend.
+
+use kctf_pow::Integer;
+
+#[test]
+fn bytes_round_trip() {
+    assert_eq!(Integer::from(0u32).to_bytes_be(), Vec::<u8>::new());
+    assert_eq!(Integer::from_bytes_be(&[]), Integer::from(0u32));
+    assert_eq!(
+        Integer::from_bytes_be(&[0, 0, 1, 2]),
+        Integer::from(0x0102u32)
+    );
+    let val = Integer::from(u128::MAX) << 3u32;
+    assert_eq!(Integer::from_bytes_be(&val.to_bytes_be()), val);
+    assert_eq!(val.to_bytes_be().len(), 17);
+}
+
+#[test]
+fn arithmetic() {
+    let a = Integer::from(1_000_000_007u32);
+    let b = Integer::from(65_537u32);
+    assert_eq!(a.clone() + &b, Integer::from(1_000_065_544u32));
+    assert_eq!(a.clone() - b.clone(), Integer::from(999_934_470u32));
+    assert_eq!(a.clone() * 3u32, Integer::from(3_000_000_021u64));
+    assert_eq!(a.clone() / b.clone(), Integer::from(15_258u32));
+    assert_eq!(a.clone() % &b, Integer::from(36_461u32));
+    assert_eq!(
+        Integer::from(4u32) | Integer::from(1u32),
+        Integer::from(5u32)
+    );
+    assert_eq!(a.clone() >> 10u32, Integer::from(976_562u32));
+    assert_eq!(
+        b.pow_mod(&Integer::from(2u32), &a),
+        Integer::from(295_098_341u32)
+    );
+    assert!(a.is_odd());
+    assert!(!(a.clone() + 1u32).is_odd());
+    let mut c = a.clone();
+    c -= 7u32;
+    c /= 1000u32;
+    assert_eq!(c, Integer::from(1_000_000u32));
+    assert!(b < a);
+}
+
+#[test]
+#[should_panic]
+fn subtracting_below_zero_panics() {
+    let _ = Integer::from(1u32) - 2u32;
+}
+
+#[test]
+fn formats_as_decimal() {
+    let val = Integer::from(1u32) << 64u32;
+    assert_eq!(val.to_string(), "18446744073709551616");
+    assert_eq!(format!("{:?}", val), "18446744073709551616");
+}
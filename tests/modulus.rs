@@ -0,0 +1,103 @@
+//! Proof-of-work systems with moduli other than kCTF's, including primes that are 1 mod 4.
+
+use kctf_pow::{ChallengeParams, Integer, KctfPow, PowError, SqrtStrategy};
+
+/// Solves then checks each starting value at a few difficulties.
+fn round_trip(pow: &KctfPow, vals: impl Iterator<Item = Integer>) {
+    for val in vals {
+        for difficulty in 0..4 {
+            let params = ChallengeParams {
+                difficulty,
+                val: val.clone(),
+            };
+            let sol = params.clone().solve(pow);
+            assert!(sol.check(&params, pow), "solving {}", params);
+        }
+    }
+}
+
+#[test]
+fn small_prime_is_exhaustive() {
+    // all of these are 1 mod 4, and 96 is divisible by 32 so Tonelli-Shanks takes several rounds
+    for modulus in [13u32, 17, 97] {
+        let pow = KctfPow::with_modulus(Integer::from(modulus)).unwrap();
+        assert!(
+            matches!(pow.sqrt, SqrtStrategy::TonelliShanks { .. }),
+            "picking a strategy for {}",
+            modulus
+        );
+        round_trip(&pow, (0..modulus).map(Integer::from));
+    }
+}
+
+#[test]
+fn curve25519_prime() {
+    let modulus = (Integer::from(1u32) << 255u32) - 19u32;
+    let pow = KctfPow::with_modulus(modulus.clone()).unwrap();
+    assert_eq!(
+        pow.sqrt,
+        SqrtStrategy::TonelliShanks {
+            non_residue: Integer::from(2u32)
+        }
+    );
+    let vals = vec![
+        Integer::from(0u32),
+        Integer::from(1u32),
+        Integer::from(0x1234_5678u32),
+        modulus.clone() - 1u32,
+        modulus / 3u32,
+    ];
+    round_trip(&pow, vals.into_iter());
+}
+
+#[test]
+fn picks_exponent_for_3_mod_4() {
+    let pow = KctfPow::with_modulus(Integer::from(23u32)).unwrap();
+    assert_eq!(pow.sqrt, SqrtStrategy::Exponent);
+    assert_eq!(pow.exponent, Integer::from(6u32));
+    round_trip(&pow, (0..23u32).map(Integer::from));
+}
+
+#[test]
+fn rejects_non_primes() {
+    let moduli = [
+        Integer::from(0u32),
+        Integer::from(1u32),
+        // even, including the only even prime
+        Integer::from(2u32),
+        Integer::from(4u32),
+        (Integer::from(1u32) << 255u32) - 18u32,
+        // composites, including the Carmichael numbers 561 and 41041
+        Integer::from(9u32),
+        Integer::from(91u32),
+        Integer::from(561u32),
+        Integer::from(41041u32),
+        Integer::from(10403u32),
+        ((Integer::from(1u32) << 61u32) - 1u32) * ((Integer::from(1u32) << 89u32) - 1u32),
+    ];
+    for modulus in moduli {
+        assert_eq!(
+            KctfPow::with_modulus(modulus.clone()),
+            Err(PowError::InvalidModulus),
+            "creating with {:?}",
+            modulus
+        );
+    }
+}
+
+#[test]
+fn small_mersenne_primes() {
+    const CHALLENGE: &str = "s.AAAAMg==.NDtqORW1uZlIgzszbdMGZA==";
+    for pow in [KctfPow::mersenne_521(), KctfPow::mersenne_607()] {
+        assert_eq!(KctfPow::with_modulus(pow.modulus.clone()), Ok(pow.clone()));
+        let chall = pow.decode_challenge(CHALLENGE).unwrap();
+        let sol = chall.clone().solve();
+        assert!(chall.check_solution(&sol));
+        assert_eq!(chall.check(&sol.to_string()), Ok(true));
+        // solutions for a different modulus don't check
+        assert!(!KctfPow::new()
+            .decode_challenge(CHALLENGE)
+            .unwrap()
+            .check_solution(&sol));
+    }
+}
@@ -0,0 +1,204 @@
+//! Answering JSON-RPC requests with the command-line interface.
+
+#![cfg(feature = "rpc")]
+
+use kctf_pow::{ChallengeParams, KctfPow, PowError};
+use serde_json::{json, Value};
+use std::io::{BufRead, BufReader, Write};
+use std::process::{Command, Stdio};
+
+const CHALLENGE: &str = "s.AAAAMg==.NDtqORW1uZlIgzszbdMGZA==";
+
+fn spawn() -> std::process::Child {
+    Command::new(env!("CARGO_BIN_EXE_kctf-pow"))
+        .arg("rpc")
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .unwrap()
+}
+
+/// Sends every line of `input` then closes stdin, and returns the responses once every request has been answered.
+fn rpc(input: &[String]) -> Vec<Value> {
+    let mut child = spawn();
+    let mut stdin = child.stdin.take().unwrap();
+    for line in input {
+        writeln!(stdin, "{}", line).unwrap();
+    }
+    drop(stdin);
+    let output = child.wait_with_output().unwrap();
+    assert!(output.status.success());
+    String::from_utf8(output.stdout)
+        .unwrap()
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect()
+}
+
+fn request(id: u32, method: &str, params: Value) -> String {
+    json!({ "jsonrpc": "2.0", "id": id, "method": method, "params": params }).to_string()
+}
+
+/// Returns the response with an id, since responses may be out of order.
+fn response(responses: &[Value], id: Value) -> &Value {
+    responses
+        .iter()
+        .find(|response| response["id"] == id)
+        .unwrap_or_else(|| panic!("no response for {}", id))
+}
+
+fn error_code(responses: &[Value], id: Value) -> i64 {
+    response(responses, id)["error"]["code"].as_i64().unwrap()
+}
+
+#[test]
+fn answers_each_method() {
+    let responses = rpc(&[
+        request(
+            1,
+            "generate",
+            json!({ "difficulty": 50, "seed": "fixture" }),
+        ),
+        request(2, "generate", json!({ "difficulty": 10, "scheme": "h" })),
+        request(3, "solve", json!({ "challenge": CHALLENGE })),
+        request(4, "solve", json!({ "challenge": "h.AAAACA==.AQ==" })),
+        request(
+            5,
+            "check",
+            json!({ "challenge": CHALLENGE, "solution": "s.AQ==" }),
+        ),
+        request(
+            6,
+            "check",
+            json!({ "challenge": CHALLENGE, "solution": "s.AQ==", "strict": true }),
+        ),
+        request(7, "inspect", json!({ "challenge": CHALLENGE })),
+        request(8, "inspect", json!({ "challenge": "h.AAAACA==.AQ==" })),
+    ]);
+    assert_eq!(responses.len(), 8);
+    assert_eq!(
+        response(&responses, 1.into())["result"],
+        "s.AAAAMg==.DZLgUpnUdPJex/5NS0K/Gw=="
+    );
+    assert!(response(&responses, 2.into())["result"]
+        .as_str()
+        .unwrap()
+        .starts_with("h.AAAACg==."));
+    let sol = response(&responses, 3.into())["result"].as_str().unwrap();
+    let pow = KctfPow::new();
+    let chall = pow.decode_challenge(CHALLENGE).unwrap();
+    assert_eq!(chall.check(sol), Ok(true));
+    assert!(response(&responses, 4.into())["result"]
+        .as_str()
+        .unwrap()
+        .starts_with("h."));
+    assert_eq!(response(&responses, 5.into())["result"], false);
+    assert_eq!(response(&responses, 6.into())["result"], false);
+    assert_eq!(
+        response(&responses, 7.into())["result"],
+        json!({ "scheme": "s", "difficulty": 50 })
+    );
+    assert_eq!(
+        response(&responses, 8.into())["result"],
+        json!({ "scheme": "h", "difficulty": 8 })
+    );
+}
+
+#[test]
+fn reports_errors() {
+    let responses = rpc(&[
+        "{".into(),
+        json!({ "jsonrpc": "2.0", "id": 1, "params": {} }).to_string(),
+        json!({ "jsonrpc": "2.0", "id": 2, "method": "inspect", "params": 5 }).to_string(),
+        request(3, "inspect", json!([CHALLENGE])),
+        request(4, "unknown", json!({})),
+        request(5, "generate", json!({ "difficulty": -1 })),
+        request(6, "generate", json!({ "difficulty": 257, "scheme": "h" })),
+        request(7, "generate", json!({ "difficulty": 1, "scheme": "x" })),
+        request(8, "solve", json!({})),
+        request(
+            9,
+            "check",
+            json!({ "challenge": "x.AQ==", "solution": "s.AQ==" }),
+        ),
+        request(10, "inspect", json!({ "challenge": "s.AAAAMg==" })),
+    ]);
+    assert_eq!(responses.len(), 11);
+    assert_eq!(error_code(&responses, Value::Null), -32700);
+    assert_eq!(error_code(&responses, 1.into()), -32600);
+    assert_eq!(error_code(&responses, 2.into()), -32600);
+    for id in [3, 5, 6, 7, 8] {
+        assert_eq!(
+            error_code(&responses, id.into()),
+            -32602,
+            "answering {}",
+            id
+        );
+    }
+    assert_eq!(error_code(&responses, 4.into()), -32601);
+    // errors from decoding have the same codes as the exit codes of the CLI
+    assert_eq!(
+        error_code(&responses, 9.into()),
+        i64::from(PowError::WrongVersion("x".into()).code())
+    );
+    assert_eq!(
+        error_code(&responses, 10.into()),
+        i64::from(PowError::WrongPartCount.code())
+    );
+}
+
+#[test]
+fn notifications_get_no_response() {
+    let notification = |method: &str, params: Value| {
+        json!({ "jsonrpc": "2.0", "method": method, "params": params }).to_string()
+    };
+    let responses = rpc(&[
+        notification("inspect", json!({ "challenge": CHALLENGE })),
+        notification("solve", json!({ "challenge": CHALLENGE })),
+        notification(
+            "check",
+            json!({ "challenge": "x.AQ==", "solution": "s.AQ==" }),
+        ),
+        notification("unknown", json!({})),
+        request(1, "inspect", json!({ "challenge": CHALLENGE })),
+    ]);
+    assert_eq!(responses.len(), 1);
+    assert_eq!(responses[0]["id"], 1);
+}
+
+#[test]
+fn checks_while_solving() {
+    let mut child = spawn();
+    let mut stdin = child.stdin.take().unwrap();
+    let mut stdout = BufReader::new(child.stdout.take().unwrap());
+    // far too difficult to finish during the test
+    let slow = ChallengeParams {
+        difficulty: 100_000_000,
+        val: 1u32.into(),
+    }
+    .to_string();
+    writeln!(
+        stdin,
+        "{}",
+        request(1, "solve", json!({ "challenge": slow }))
+    )
+    .unwrap();
+    writeln!(
+        stdin,
+        "{}",
+        request(
+            2,
+            "check",
+            json!({ "challenge": CHALLENGE, "solution": "s.AQ==" })
+        )
+    )
+    .unwrap();
+    stdin.flush().unwrap();
+    let mut line = String::new();
+    stdout.read_line(&mut line).unwrap();
+    let response: Value = serde_json::from_str(&line).unwrap();
+    assert_eq!(response["id"], 2);
+    assert_eq!(response["result"], false);
+    child.kill().unwrap();
+    child.wait().unwrap();
+}
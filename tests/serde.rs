@@ -0,0 +1,143 @@
+//! Serializing challenges and solutions in their string and structured forms.
+
+#![cfg(feature = "serde")]
+
+use kctf_pow::{ChallengeParams, Solution};
+use serde::de::value::Error;
+use serde::de::{Deserialize, Deserializer, Visitor};
+use serde::forward_to_deserialize_any;
+
+const CHALLENGE: &str = "s.AAAAMg==.NDtqORW1uZlIgzszbdMGZA==";
+const SOLUTION: &str = "s.AQI=";
+
+#[derive(Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+struct Structured {
+    #[serde(with = "kctf_pow::serde_structured")]
+    chall: ChallengeParams,
+    #[serde(with = "kctf_pow::serde_structured")]
+    sol: Solution,
+}
+
+/// A string in a format that isn't human-readable or self-describing, which only allows deserializing what's expected.
+struct Binary<'a>(&'a str);
+
+impl<'de, 'a> Deserializer<'de> for Binary<'a> {
+    type Error = Error;
+
+    fn deserialize_any<V: Visitor<'de>>(self, _: V) -> Result<V::Value, Error> {
+        Err(serde::de::Error::custom("the format isn't self-describing"))
+    }
+
+    fn deserialize_str<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        visitor.visit_str(self.0)
+    }
+
+    fn is_human_readable(&self) -> bool {
+        false
+    }
+
+    forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char string
+        bytes byte_buf option unit unit_struct newtype_struct seq tuple
+        tuple_struct map struct enum identifier ignored_any
+    }
+}
+
+fn error<T: for<'de> Deserialize<'de>>(json: &str) -> String {
+    match serde_json::from_str::<T>(json) {
+        Ok(_) => panic!("deserializing {}", json),
+        Err(err) => err.to_string(),
+    }
+}
+
+#[test]
+fn round_trips() {
+    let chall: ChallengeParams = CHALLENGE.parse().unwrap();
+    let sol: Solution = SOLUTION.parse().unwrap();
+    let json = serde_json::to_string(&chall).unwrap();
+    assert_eq!(json, format!("{:?}", CHALLENGE));
+    assert_eq!(
+        serde_json::from_str::<ChallengeParams>(&json).unwrap(),
+        chall
+    );
+    let json = serde_json::to_string(&sol).unwrap();
+    assert_eq!(json, format!("{:?}", SOLUTION));
+    assert_eq!(serde_json::from_str::<Solution>(&json).unwrap(), sol);
+
+    let structured = Structured { chall, sol };
+    let json = serde_json::to_string(&structured).unwrap();
+    assert_eq!(
+        json,
+        r#"{"chall":{"difficulty":50,"value":"NDtqORW1uZlIgzszbdMGZA=="},"sol":{"value":"AQI="}}"#
+    );
+    assert_eq!(
+        serde_json::from_str::<Structured>(&json).unwrap(),
+        structured
+    );
+}
+
+#[test]
+fn non_human_readable_uses_strings() {
+    let chall = ChallengeParams::deserialize(Binary(CHALLENGE)).unwrap();
+    assert_eq!(chall, CHALLENGE.parse().unwrap());
+    let sol = Solution::deserialize(Binary(SOLUTION)).unwrap();
+    assert_eq!(sol, SOLUTION.parse().unwrap());
+    assert!(Solution::deserialize(Binary("x.AQ==")).is_err());
+}
+
+#[test]
+fn structures_can_be_sequences() {
+    let json = r#"{"chall":[50,"NDtqORW1uZlIgzszbdMGZA=="],"sol":["AQI="]}"#;
+    let structured: Structured = serde_json::from_str(json).unwrap();
+    assert_eq!(structured.chall, CHALLENGE.parse().unwrap());
+    assert_eq!(structured.sol, SOLUTION.parse().unwrap());
+    // the default deserializer accepts the structured form too
+    assert_eq!(
+        serde_json::from_str::<ChallengeParams>(r#"[50,"NDtqORW1uZlIgzszbdMGZA=="]"#).unwrap(),
+        structured.chall
+    );
+    assert!(error::<ChallengeParams>("[50]").contains("invalid length 1"));
+    assert!(error::<ChallengeParams>("[]").contains("invalid length 0"));
+    assert!(error::<Solution>("[]").contains("invalid length 0"));
+    assert!(error::<Solution>(r#"["AQI*"]"#).contains("base64"));
+}
+
+#[test]
+fn rejects_bad_fields() {
+    let cases = [
+        (
+            r#"{"difficulty":50,"difficulty":50,"value":"AQI="}"#,
+            "duplicate field `difficulty`",
+        ),
+        (
+            r#"{"difficulty":50,"value":"AQI=","value":"AQI="}"#,
+            "duplicate field `value`",
+        ),
+        (
+            r#"{"difficulty":50,"value":"AQI=","extra":1}"#,
+            "unknown field `extra`",
+        ),
+        (r#"{"value":"AQI="}"#, "missing field `difficulty`"),
+        (r#"{"difficulty":50}"#, "missing field `value`"),
+    ];
+    for (json, message) in cases {
+        let err = error::<ChallengeParams>(json);
+        assert!(err.contains(message), "deserializing {}: {}", json, err);
+    }
+    let cases = [
+        (
+            r#"{"value":"AQI=","value":"AQI="}"#,
+            "duplicate field `value`",
+        ),
+        // solutions don't have a difficulty
+        (
+            r#"{"difficulty":50,"value":"AQI="}"#,
+            "unknown field `difficulty`",
+        ),
+        ("{}", "missing field `value`"),
+    ];
+    for (json, message) in cases {
+        let err = error::<Solution>(json);
+        assert!(err.contains(message), "deserializing {}: {}", json, err);
+    }
+}
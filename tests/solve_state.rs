@@ -0,0 +1,87 @@
+//! Saving, loading, and resuming partially finished solves.
+
+use kctf_pow::{Cancelled, ChallengeParams, KctfPow, PowError, SolveState};
+use std::ops::ControlFlow;
+
+const CHALLENGE: &str = "s.AAAAMg==.NDtqORW1uZlIgzszbdMGZA==";
+
+/// Solves a challenge until `stop` iterations are done, then cancels it.
+fn cancel_at(pow: &KctfPow, params: ChallengeParams, stop: u32) -> SolveState {
+    let mut state = SolveState::new(params);
+    let res = state.solve_with(pow, 10, |done, _| {
+        if done >= stop {
+            ControlFlow::Break(())
+        } else {
+            ControlFlow::Continue(())
+        }
+    });
+    assert_eq!(res, Err(Cancelled));
+    state
+}
+
+#[test]
+fn round_trips() {
+    let pow = KctfPow::new();
+    let params: ChallengeParams = CHALLENGE.parse().unwrap();
+    let states = [
+        SolveState::new(params.clone()),
+        cancel_at(&pow, params.clone(), 20),
+        SolveState::new("s.AAAAAA==.".parse().unwrap()),
+    ];
+    for state in states {
+        assert_eq!(state.to_string().parse::<SolveState>(), Ok(state.clone()));
+        assert_eq!(SolveState::from_bytes(&state.to_bytes()), Ok(state.clone()));
+    }
+}
+
+#[test]
+fn resumes_after_cancel() {
+    for pow in [KctfPow::new(), KctfPow::mersenne_521()] {
+        let params: ChallengeParams = CHALLENGE.parse().unwrap();
+        let expected = params.clone().solve(&pow);
+        let state = cancel_at(&pow, params, 20);
+        assert_eq!(state.done, 20);
+        assert!(!state.is_done());
+        assert_eq!(state.solution(), None);
+
+        let loaded = [
+            state.to_string().parse::<SolveState>().unwrap(),
+            SolveState::from_bytes(&state.to_bytes()).unwrap(),
+        ];
+        for mut state in loaded {
+            // progress is only reported once the solve has moved past where it was resumed from
+            let mut reported = Vec::new();
+            let sol = state.solve_with(&pow, 10, |done, total| {
+                reported.push((done, total));
+                ControlFlow::Continue(())
+            });
+            assert_eq!(sol.as_ref(), Ok(&expected));
+            assert_eq!(reported, [(30, 50), (40, 50), (50, 50)]);
+            assert!(state.is_done());
+            assert_eq!(state.solution(), Some(expected.clone()));
+        }
+    }
+}
+
+#[test]
+fn rejects_invalid_states() {
+    let params: ChallengeParams = CHALLENGE.parse().unwrap();
+    let bytes = SolveState::new(params).to_bytes();
+    assert_eq!(
+        SolveState::from_bytes(&bytes[..11]),
+        Err(PowError::Truncated)
+    );
+    // the length of the starting value is longer than the rest of the bytes
+    assert_eq!(
+        SolveState::from_bytes(&bytes[..20]),
+        Err(PowError::Truncated)
+    );
+    assert_eq!(
+        SolveState::decode_state("s.AAAAMg==.NDtqORW1uZlIgzszbdMGZA==.AAAAMw==."),
+        Err(PowError::ValueOutOfRange)
+    );
+    assert_eq!(
+        SolveState::decode_state(CHALLENGE),
+        Err(PowError::WrongPartCount)
+    );
+}
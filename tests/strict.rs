@@ -0,0 +1,141 @@
+//! Strict decoding, which has to accept everything that kCTF's `pow.py` produces while rejecting malformed input.
+
+use kctf_pow::{KctfPow, PowError, Solution};
+
+/// Challenges generated and solved by `pow.py`, which pads numbers with leading zeros to a multiple of 3 bytes.
+const KCTF_VECTORS: [(&str, &str); 5] = [
+    (
+        "s.AAAB.AABfSF1Yn8Q0sHz4s6q7r/ui",
+        "s.AAA8de5IS5SVj6Oc9VTgDAjaqjJrhY1lSsh1j2eHJ0yzX/fDW4V1gdW2KN/h01nwKnR79xqVaVO9ySpf7xObxEGu2uQ5swYMKkn0l/O71L84rooXROQk1UEDavEu8EE3vuFogWYpltF6BTRVy5ZyUrGd+PN2yR5xK3touJCctj0uM02FsK8nSxVEjYNl9A+gK9BrL4303rsFq1q3tGWXZKcb",
+    ),
+    (
+        "s.AAAC.AACrNon/HLkAT1BtKdFZIf7/",
+        "s.AABwvVoC5TuIKISpX4m7XmF5LADvx3HfJkWmZDTknl6xfwheV8U6MECCgKPAgRU6ksV/7ti23qBD6pbj7i0YvGirYcyMceBiXqhytLPMojKx6ZFOTg2kF3BUVmPbyJdggLZvF5t7kqZXmoR/JxOplwd4iEKQjMDSCXGV8Z8pdihMfmlrB/lpWFGhaHEQQkjiyRVWL3mRy/o8dQq5B/xuqyTr",
+    ),
+    (
+        "s.AAAK.AABlNHXm+t9maf9mXjYJ4T+8",
+        "s.AABnHEjFhwMtb3EN19v2CFnfVdyZNIyCCNpJU6H2BFxbubS3SpHyaeSSKNsBrhCQYkrHEeiDNi6HQh5lO7chifaKsuR/NfAJDk5x6wwRgR4aFYB19NHxACCHlQoEbK7vuA83NXwFLnSoC4aLSZZbGfsweoiUgBc4AJfZYWzWopB40IhmlZ37bs94X9VlIEA+TdCanMyMPepBzC7iBRnHgW6V",
+    ),
+    (
+        "s.AAAf.AACDZZbjHHZW76AiRbJTMtzj",
+        "s.AAByG8WvOXOiHCI/PiaiL0CtDr23FUv9LV14lVYQOpEK7reOhVEHzDmRLpXbpH9wJAi3cJiVO170vtDwkQ6GYqr4F3uNtuBmPz/BpkY/qizi9EJHkDl8UndttAMz/4IM7kUlFyWy4B4Jvm4kAicOiaeIheyN+NnDXp3PpGif+l7nMZ8Sl+wYLS2TX+Q1e0Gvq/Zj2X2+U99vKH6rAJCZefKQ",
+    ),
+    (
+        "s.AABk.AADPwwMEZd+8vjgXIoyFN7Ij",
+        "s.AAB/UJxed8EWANO3PleVa6ZSYFXuWMVNHHDqezedMRG4LAenfbrJCHeiT9Ap+wgw3E4JZ/T7gCYpqgFl0fJDfZziothe1A4PFJ7qNVj3OV4qdTlI2x+6qwmt2ocZRE4WNTox8AIFecCIwp749GYAn5jyYw8nwy8TWVdbwUsgqV9IMDMXeob7xXq+I5dghHyB1YyXxVpv9pqZCD7CXi3S2fio",
+    ),
+];
+
+const CHALLENGE: &str = "s.AAAAMg==.NDtqORW1uZlIgzszbdMGZA==";
+const SOLUTION: &str = "s.NUH3arymnKB+ysUGdv+67ypDamn4wOKCPORB2ivWE1Yhinam2v4S6q4nAoC5LP97LScdVoq+NuFVF++Win5mNRYZS6bJAs8fk0h8XgvfcC/7JfmFISqeCIo/CIUgIucVAM+eGDjqitRULGXqIOyviJoJjW8DMouMRuJM/3eg/z18kutQHkX0N3sqPeF7Nzkk8S3Bs6aiHUORM30syUKYug==";
+
+#[test]
+fn accepts_kctf_vectors() {
+    let pow = KctfPow::new();
+    for (chall, sol) in KCTF_VECTORS {
+        let chall = pow.decode_challenge_strict(chall).unwrap();
+        assert_eq!(chall.check_strict(sol), Ok(true), "checking {}", chall);
+        assert_eq!(chall.check(sol), Ok(true), "checking {}", chall);
+    }
+}
+
+#[test]
+fn accepts_own_encoding() {
+    let pow = KctfPow::new();
+    let chall = pow.decode_challenge_strict(CHALLENGE).unwrap();
+    assert_eq!(chall.check_strict(SOLUTION), Ok(true));
+    assert_eq!(chall.check_strict("s."), Ok(false));
+}
+
+#[test]
+fn rejects_too_long() {
+    let pow = KctfPow::new();
+    let long_val = "A".repeat(220);
+    assert_eq!(
+        pow.decode_challenge_strict(&format!("s.AAAAMg==.{}", long_val)),
+        Err(PowError::TooLong)
+    );
+    assert_eq!(
+        Solution::decode_solution_strict(&format!("s.{}", long_val), &pow),
+        Err(PowError::TooLong)
+    );
+    // a value padded past the next multiple of 3 bytes is always too long
+    let padded = Solution::from_bytes(&[1; 160]).to_string();
+    assert!(Solution::decode_solution_strict(&padded, &pow).is_ok());
+    let padded = format!("s.AAAA{}", &padded[2..]);
+    assert_eq!(
+        Solution::decode_solution_strict(&padded, &pow),
+        Err(PowError::TooLong)
+    );
+}
+
+#[test]
+fn rejects_non_canonical() {
+    let pow = KctfPow::new();
+    for chall in [
+        // missing padding
+        "s.AAAAMg.NDtqORW1uZlIgzszbdMGZA==",
+        // nonzero trailing bits
+        "s.AAAAMh==.NDtqORW1uZlIgzszbdMGZA==",
+        // more leading zeros than kCTF pads a difficulty to
+        "s.AAAAAAAAMg==.NDtqORW1uZlIgzszbdMGZA==",
+    ] {
+        assert_eq!(
+            pow.decode_challenge_strict(chall),
+            Err(PowError::NonCanonical),
+            "decoding {}",
+            chall
+        );
+    }
+    for sol in ["s.AQ", "s.AR==", "s.AQ="] {
+        assert_eq!(
+            Solution::decode_solution_strict(sol, &pow),
+            Err(PowError::NonCanonical),
+            "decoding {}",
+            sol
+        );
+    }
+}
+
+#[test]
+fn rejects_out_of_range() {
+    let pow = KctfPow::new();
+    let modulus = Solution {
+        val: pow.modulus.clone(),
+    };
+    let chall = format!("s.AAAAMg==.{}", &modulus.to_string()[2..]);
+    assert_eq!(
+        pow.decode_challenge_strict(&chall),
+        Err(PowError::ValueOutOfRange)
+    );
+    // the modulus itself can be a solution
+    assert!(Solution::decode_solution_strict(&modulus.to_string(), &pow).is_ok());
+    let above = Solution {
+        val: pow.modulus.clone() + 1u32,
+    };
+    assert_eq!(
+        Solution::decode_solution_strict(&above.to_string(), &pow),
+        Err(PowError::ValueOutOfRange)
+    );
+}
+
+#[test]
+fn passes_through_decoding_errors() {
+    let pow = KctfPow::new();
+    assert_eq!(
+        pow.decode_challenge_strict("t.AAAAMg==.AQ=="),
+        Err(PowError::WrongVersion("t".into()))
+    );
+    assert_eq!(
+        pow.decode_challenge_strict("s.AAAAMg=="),
+        Err(PowError::WrongPartCount)
+    );
+    assert_eq!(
+        Solution::decode_solution_strict("s.A*==", &pow),
+        Err(PowError::InvalidBase64 { part: 1 })
+    );
+    assert_eq!(
+        pow.decode_challenge_strict("s.AQAAAAAA.AQ=="),
+        Err(PowError::DifficultyTooLarge)
+    );
+}
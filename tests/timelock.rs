@@ -0,0 +1,69 @@
+//! Opening time-lock puzzles that may have been modified or crafted by someone else.
+
+use kctf_pow::{PowError, TimeLock};
+use rand::SeedableRng;
+use rand_chacha::ChaCha20Rng;
+
+fn seal(payload: &[u8]) -> TimeLock {
+    let mut rng = ChaCha20Rng::seed_from_u64(17);
+    TimeLock::seal_with_bits(&mut rng, payload, 100, 256)
+}
+
+#[test]
+fn round_trips() {
+    let puzzle = seal(b"flag{patience}");
+    let puzzle: TimeLock = puzzle.to_string().parse().unwrap();
+    assert_eq!(puzzle.open(), Ok(b"flag{patience}".to_vec()));
+}
+
+#[test]
+fn small_moduli_round_trip() {
+    // small primes are few enough that drawing the same one twice is likely unless it's ruled out
+    for bits in [10, 11, 12, 16, 24] {
+        for seed in 0..50 {
+            let mut rng = ChaCha20Rng::seed_from_u64(seed);
+            let puzzle = TimeLock::seal_with_bits(&mut rng, b"flag{patience}", 10, bits);
+            assert_eq!(
+                puzzle.open(),
+                Ok(b"flag{patience}".to_vec()),
+                "opening {}-bit puzzle with seed {}",
+                bits,
+                seed
+            );
+        }
+    }
+}
+
+#[test]
+fn rejects_tampering() {
+    let puzzle = seal(b"flag{patience}");
+    // flip a bit of the payload, then of the tag
+    for i in [0, puzzle.ciphertext.len() - 1] {
+        let mut tampered = puzzle.clone();
+        tampered.ciphertext[i] ^= 1;
+        assert_eq!(tampered.open(), Err(PowError::AuthenticationFailed));
+    }
+    let mut truncated = puzzle.clone();
+    truncated.ciphertext.truncate(31);
+    assert_eq!(truncated.open(), Err(PowError::AuthenticationFailed));
+    let mut fewer_iterations = puzzle;
+    fewer_iterations.iterations -= 1;
+    assert_eq!(fewer_iterations.open(), Err(PowError::AuthenticationFailed));
+}
+
+#[test]
+fn rejects_invalid_moduli() {
+    // empty, zero, one, and even moduli
+    for modulus in ["", "AA==", "AQ==", "Ag==", "AAAC"] {
+        let puzzle = format!("t.AAAAAAAAAAE=.{}.AQ==.AAAA", modulus);
+        assert_eq!(
+            puzzle.parse::<TimeLock>(),
+            Err(PowError::InvalidModulus),
+            "decoding {}",
+            puzzle
+        );
+    }
+    let mut puzzle = seal(b"flag{patience}");
+    puzzle.modulus = 0u32.into();
+    assert_eq!(puzzle.open(), Err(PowError::InvalidModulus));
+}
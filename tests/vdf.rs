@@ -0,0 +1,89 @@
+//! The verifiable delay function, which has to accept exactly one output for each input.
+
+use kctf_pow::vdf::{self, Output};
+use kctf_pow::{Integer, KctfPow, SqrtStrategy};
+
+/// Systems using each square root strategy, with moduli small enough that evaluating is quick.
+fn systems() -> Vec<KctfPow> {
+    let curve25519 = KctfPow::with_modulus((Integer::from(1u32) << 255u32) - 19u32).unwrap();
+    assert!(matches!(
+        curve25519.sqrt,
+        SqrtStrategy::TonelliShanks { .. }
+    ));
+    vec![KctfPow::new(), KctfPow::mersenne_521(), curve25519]
+}
+
+#[test]
+fn round_trips() {
+    for pow in systems() {
+        for iterations in [0, 1, 2, 50] {
+            let output = vdf::eval(&pow, b"round 1", iterations);
+            assert!(output.val < pow.modulus);
+            assert!(vdf::verify(&pow, b"round 1", iterations, &output));
+            assert_eq!(Output::from_bytes(&output.to_bytes()), output);
+            assert!(!vdf::verify(&pow, b"round 2", iterations, &output));
+            assert!(!vdf::verify(&pow, b"round 1", iterations + 1, &output));
+        }
+    }
+}
+
+#[test]
+fn zero_iterations_hash_the_input() {
+    for pow in systems() {
+        let hash = vdf::hash_to_field(&pow, b"round 1");
+        let output = vdf::eval(&pow, b"round 1", 0);
+        match pow.sqrt {
+            // the hash or its negation, whichever is smaller
+            SqrtStrategy::Exponent => {
+                let neg = pow.modulus.clone() - hash.clone();
+                assert_eq!(output.val, if neg < hash { neg } else { hash });
+            }
+            SqrtStrategy::TonelliShanks { .. } => assert_eq!(output.val, hash),
+        }
+    }
+}
+
+#[test]
+fn outputs_are_unique() {
+    for pow in systems() {
+        for iterations in [0, 1, 50] {
+            let output = vdf::eval(&pow, b"round 1", iterations);
+            let others = [
+                // checking a challenge accepts this when the sloth function loses the sign, but it isn't the smaller one
+                pow.modulus.clone() - output.val.clone(),
+                output.val.clone() + pow.modulus.clone(),
+                pow.modulus.clone(),
+                output.val.clone() + 1u32,
+            ];
+            for val in others {
+                assert!(
+                    !vdf::verify(&pow, b"round 1", iterations, &Output { val: val.clone() }),
+                    "verifying {:?} after {} iterations",
+                    val,
+                    iterations
+                );
+            }
+        }
+    }
+}
+
+#[test]
+fn hashes_below_modulus() {
+    let mut systems = systems();
+    // small moduli make the hash wrap around many times
+    for modulus in [13u32, 23, 65521] {
+        systems.push(KctfPow::with_modulus(Integer::from(modulus)).unwrap());
+    }
+    for pow in systems {
+        let mut hashes = Vec::new();
+        for seed in 0..100u32 {
+            let hash = vdf::hash_to_field(&pow, &seed.to_be_bytes());
+            assert!(hash < pow.modulus, "hashing {} mod {:?}", seed, pow.modulus);
+            hashes.push(hash);
+        }
+        // the hash depends on the seed
+        hashes.sort();
+        hashes.dedup();
+        assert!(hashes.len() > 1);
+    }
+}
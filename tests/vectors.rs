@@ -0,0 +1,64 @@
+//! Fixed challenges and solutions, computed with kCTF's reference algorithm, that both big integer backends must reproduce byte for byte.
+//!
+//! Run them under each backend with `cargo test --test vectors` and
+//! `cargo test --test vectors --no-default-features --features pure-rust,std`.
+
+use kctf_pow::{ChallengeParams, KctfPow, Solution, SolveState};
+
+const VECTORS: [(&str, &str); 7] = [
+    (
+        "s.AAAAMg==.NDtqORW1uZlIgzszbdMGZA==",
+        "s.NUH3arymnKB+ysUGdv+67ypDamn4wOKCPORB2ivWE1Yhinam2v4S6q4nAoC5LP97LScdVoq+NuFVF++Win5mNRYZS6bJAs8fk0h8XgvfcC/7JfmFISqeCIo/CIUgIucVAM+eGDjqitRULGXqIOyviJoJjW8DMouMRuJM/3eg/z18kutQHkX0N3sqPeF7Nzkk8S3Bs6aiHUORM30syUKYug==",
+    ),
+    (
+        "s.AAAAMg==.H+fPiuL32DPbfN97cpd0nA==",
+        "s.O5X5tBMcDT3O2E/32edB/FqCuws5LuvMKGGAkqVc9Wak/gJmwkUpUvYWOlr9x+tsccb6/KcNCQTym1Jzclv+aXE49pu5RkukYgijK8gbuuQrfp+YIJ6OFHId2tCIAdV/QYFIrhUy1pVUZ6mGCCCRjGqMVSo6QGDAS59tKKbnGjdZYRLSku30L9GWpSx9Sdjas/PzTxOsN6rjlCBE/qgGHg==",
+    ),
+    // zero is encoded as no bytes at all
+    ("s.AAAAAA==.", "s."),
+    ("s.AAAAAQ==.", "s.AQ=="),
+    ("s.AAAAAg==.", "s."),
+    ("s.AAAAAw==.", "s.AQ=="),
+    ("s.AAAAZA==.AQ==", "s.AQ=="),
+];
+
+#[test]
+fn solve_matches_vectors() {
+    let pow = KctfPow::new();
+    for (chall, sol) in VECTORS {
+        let params: ChallengeParams = chall.parse().unwrap();
+        assert_eq!(params.solve(&pow).to_string(), sol, "solving {}", chall);
+    }
+}
+
+#[test]
+fn check_matches_vectors() {
+    let pow = KctfPow::new();
+    for (chall, sol) in VECTORS {
+        let params: ChallengeParams = chall.parse().unwrap();
+        assert_eq!(params.check(&pow, sol), Ok(true), "checking {}", chall);
+    }
+}
+
+#[test]
+fn encoding_round_trips() {
+    for (chall, sol) in VECTORS.iter() {
+        assert_eq!(
+            chall.parse::<ChallengeParams>().unwrap().to_string(),
+            *chall
+        );
+        assert_eq!(sol.parse::<Solution>().unwrap().to_string(), *sol);
+    }
+}
+
+#[test]
+fn zero_has_no_bytes() {
+    for bytes in [&[][..], &[0], &[0, 0, 0]] {
+        let sol = Solution::from_bytes(bytes);
+        assert_eq!(sol.to_bytes(), Vec::<u8>::new());
+        assert_eq!(sol.to_string(), "s.");
+    }
+    let state = SolveState::new("s.AAAAAQ==.".parse().unwrap());
+    assert_eq!(state.to_string(), "s.AAAAAQ==..AAAAAA==.");
+    assert_eq!(state.to_bytes(), [0, 0, 0, 1, 0, 0, 0, 0, 0, 0, 0, 0]);
+}
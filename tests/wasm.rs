@@ -0,0 +1,43 @@
+//! Tests for the WebAssembly bindings, which run under `wasm32-unknown-unknown` with `wasm-bindgen-test-runner`:
+//!
+//! ```text
+//! CARGO_TARGET_WASM32_UNKNOWN_UNKNOWN_RUNNER=wasm-bindgen-test-runner \
+//!     cargo test --target wasm32-unknown-unknown --no-default-features --features wasm --test wasm
+//! ```
+
+#![cfg(all(target_arch = "wasm32", feature = "wasm"))]
+
+use js_sys::Function;
+use kctf_pow::{wasm, ChallengeParams, KctfPow};
+use wasm_bindgen_test::wasm_bindgen_test;
+
+const CHALLENGE: &str = "s.AAAAMg==.NDtqORW1uZlIgzszbdMGZA==";
+const SOLUTION: &str = "s.NUH3arymnKB+ysUGdv+67ypDamn4wOKCPORB2ivWE1Yhinam2v4S6q4nAoC5LP97LScdVoq+NuFVF++Win5mNRYZS6bJAs8fk0h8XgvfcC/7JfmFISqeCIo/CIUgIucVAM+eGDjqitRULGXqIOyviJoJjW8DMouMRuJM/3eg/z18kutQHkX0N3sqPeF7Nzkk8S3Bs6aiHUORM30syUKYug==";
+
+#[wasm_bindgen_test]
+fn solve_matches_native() {
+    let native = ChallengeParams::decode_challenge(CHALLENGE)
+        .unwrap()
+        .solve(&KctfPow::new());
+    assert_eq!(wasm::solve(CHALLENGE).unwrap(), native.to_string());
+    assert_eq!(wasm::solve(CHALLENGE).unwrap(), SOLUTION);
+}
+
+#[wasm_bindgen_test]
+fn solve_with_progress() {
+    let progress = Function::new_with_args("done, total", "return total === 50;");
+    assert_eq!(
+        wasm::solve_with(CHALLENGE, 10, &progress).unwrap(),
+        SOLUTION
+    );
+    let cancel = Function::new_with_args("done, total", "return done < 20;");
+    assert!(wasm::solve_with(CHALLENGE, 10, &cancel).is_err());
+}
+
+#[wasm_bindgen_test]
+fn check() {
+    assert!(wasm::check(CHALLENGE, SOLUTION).unwrap());
+    assert!(!wasm::check(CHALLENGE, "s.asdf").unwrap());
+    assert!(wasm::check(CHALLENGE, "t.asdf").is_err());
+    assert!(wasm::check("s.asdf", SOLUTION).is_err());
+}
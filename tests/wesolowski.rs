@@ -0,0 +1,165 @@
+//! Delay challenges with Wesolowski proofs, which have to reject any output or proof that wasn't computed honestly.
+
+use kctf_pow::{DelayParams, DelaySolution, Integer, PowError, Wesolowski};
+use rand::SeedableRng;
+use rand_chacha::ChaCha20Rng;
+
+fn vdf() -> Wesolowski {
+    Wesolowski::generate(&mut ChaCha20Rng::seed_from_u64(15), 512)
+}
+
+/// Returns whichever of a value and its negation is smaller, which is the only form of a value that checking accepts.
+fn canonical(vdf: &Wesolowski, val: Integer) -> Integer {
+    let val = val % vdf.modulus.clone();
+    let neg = vdf.modulus.clone() - val.clone();
+    if neg < val {
+        neg
+    } else {
+        val
+    }
+}
+
+fn solve(vdf: &Wesolowski, iterations: u64) -> (DelayParams, DelaySolution) {
+    let mut rng = ChaCha20Rng::seed_from_u64(iterations);
+    let params = vdf.generate_challenge_with_rng(&mut rng, iterations).params;
+    let sol = params.clone().solve(vdf);
+    (params, sol)
+}
+
+#[test]
+fn round_trips() {
+    let vdf = vdf();
+    for iterations in [0, 1, 2, 127, 1000] {
+        let (params, sol) = solve(&vdf, iterations);
+        assert!(sol.check(&params, &vdf), "solving {}", params);
+        assert_eq!(params.check(&vdf, &sol.to_string()), Ok(true));
+    }
+}
+
+#[test]
+fn rejects_forgeries() {
+    let vdf = vdf();
+    let (params, sol) = solve(&vdf, 1000);
+    let forged_output = DelaySolution {
+        output: canonical(&vdf, sol.output.clone() + 1u32),
+        proof: sol.proof.clone(),
+    };
+    assert!(!forged_output.check(&params, &vdf));
+    let forged_proof = DelaySolution {
+        output: sol.output.clone(),
+        proof: canonical(&vdf, sol.proof.clone() + 1u32),
+    };
+    assert!(!forged_proof.check(&params, &vdf));
+    // a correct solution for one challenge doesn't check for another
+    let (other_params, other_sol) = solve(&vdf, 999);
+    assert!(!other_sol.check(&params, &vdf));
+    assert!(!sol.check(&other_params, &vdf));
+    let mut fewer_iterations = params;
+    fewer_iterations.iterations -= 1;
+    assert!(!sol.check(&fewer_iterations, &vdf));
+}
+
+#[test]
+fn rejects_non_canonical_values() {
+    let vdf = vdf();
+    let (params, sol) = solve(&vdf, 1000);
+    let modulus = vdf.modulus.clone();
+    let zero = Integer::from(0u32);
+    let non_canonical = [
+        // the negation is equal up to sign, but isn't the smaller one
+        modulus.clone() - sol.output.clone(),
+        sol.output.clone() + modulus.clone(),
+        zero.clone(),
+    ];
+    for output in non_canonical.iter() {
+        let sol = DelaySolution {
+            output: output.clone(),
+            proof: sol.proof.clone(),
+        };
+        assert!(!sol.check(&params, &vdf), "checking output {:?}", output);
+    }
+    let non_canonical = [
+        modulus.clone() - sol.proof.clone(),
+        sol.proof.clone() + modulus.clone(),
+        zero,
+    ];
+    for proof in non_canonical.iter() {
+        let sol = DelaySolution {
+            output: sol.output.clone(),
+            proof: proof.clone(),
+        };
+        assert!(!sol.check(&params, &vdf), "checking proof {:?}", proof);
+    }
+    // a starting value of zero has a trivial output
+    let zero_params = DelayParams {
+        iterations: 10,
+        val: modulus,
+    };
+    let zero_sol = zero_params.clone().solve(&vdf);
+    assert!(!zero_sol.check(&zero_params, &vdf));
+}
+
+#[test]
+fn encoding_round_trips() {
+    let vdf = vdf();
+    let (params, sol) = solve(&vdf, 100);
+    assert_eq!(
+        params.to_string().parse::<DelayParams>(),
+        Ok(params.clone())
+    );
+    assert_eq!(sol.to_string().parse::<DelaySolution>(), Ok(sol.clone()));
+    assert!(params.to_string().starts_with("w."));
+    assert_eq!(
+        "s.AAAAMg==.AQ==".parse::<DelayParams>(),
+        Err(PowError::WrongVersion("s".into()))
+    );
+    assert_eq!(
+        "w.AQ==".parse::<DelaySolution>(),
+        Err(PowError::WrongPartCount)
+    );
+}
+
+#[test]
+fn small_moduli_round_trip() {
+    for bits in [10, 11, 12, 16] {
+        for seed in 0..50 {
+            let vdf = Wesolowski::generate(&mut ChaCha20Rng::seed_from_u64(seed), bits);
+            let (params, sol) = solve(&vdf, 10);
+            assert!(
+                sol.check(&params, &vdf),
+                "solving {} with {:?}",
+                params,
+                vdf.modulus
+            );
+        }
+    }
+}
+
+#[test]
+fn with_modulus_needs_odd_composite() {
+    let vdf = vdf();
+    assert_eq!(
+        Wesolowski::with_modulus(vdf.modulus.clone()),
+        Ok(vdf.clone())
+    );
+    assert!(Wesolowski::with_modulus(Integer::from(15u32)).is_ok());
+    let moduli = [
+        Integer::from(0u32),
+        Integer::from(1u32),
+        // primes
+        Integer::from(13u32),
+        (Integer::from(1u32) << 127u32) - 1u32,
+        // even numbers
+        Integer::from(2u32),
+        Integer::from(30u32),
+        vdf.modulus.clone() * 2u32,
+    ];
+    for modulus in moduli {
+        assert_eq!(
+            Wesolowski::with_modulus(modulus.clone()),
+            Err(PowError::InvalidModulus),
+            "creating with {:?}",
+            modulus
+        );
+    }
+}